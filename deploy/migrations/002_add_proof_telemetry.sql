@@ -0,0 +1,33 @@
+-- Per-proof telemetry for capacity planning:
+--   user_cycles:        guest execution cycles reported by the prover
+--   receipt_size_bytes: size of the stored receipt (after compression, if enabled)
+
+ALTER TABLE verdict_proofs ADD COLUMN IF NOT EXISTS user_cycles bigint;
+ALTER TABLE verdict_proofs ADD COLUMN IF NOT EXISTS receipt_size_bytes integer;
+
+-- complete_proof overload taking the telemetry; the prover calls this one.
+-- Delegates to the existing 8-argument function so completion logic lives in
+-- one place.
+CREATE OR REPLACE FUNCTION complete_proof(
+  p_proof_id text,
+  p_image_id text,
+  p_receipt bytea,
+  p_journal bytea,
+  p_proving_duration_ms integer,
+  p_proving_cost numeric,
+  p_verified boolean,
+  p_verified_at timestamptz,
+  p_user_cycles bigint,
+  p_receipt_size_bytes integer
+) RETURNS void AS $$
+BEGIN
+  PERFORM complete_proof(
+    p_proof_id, p_image_id, p_receipt, p_journal,
+    p_proving_duration_ms, p_proving_cost, p_verified, p_verified_at
+  );
+  UPDATE verdict_proofs
+     SET user_cycles = p_user_cycles,
+         receipt_size_bytes = p_receipt_size_bytes
+   WHERE proof_id = p_proof_id;
+END;
+$$ LANGUAGE plpgsql;
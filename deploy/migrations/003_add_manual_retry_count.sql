@@ -0,0 +1,4 @@
+-- Count of manual requeues via POST /prove/:id/requeue, kept separate from
+-- retry_count (which the requeue resets so the retry loop tries again).
+
+ALTER TABLE verdict_proofs ADD COLUMN IF NOT EXISTS manual_retry_count integer NOT NULL DEFAULT 0;
@@ -0,0 +1,38 @@
+//! Cross-language conformance tests for `hash_concerns`.
+//!
+//! Vectors live in `tests/fixtures/conformance/hash_concerns.json` and were
+//! produced independently of this crate. A failure here means the Rust
+//! normalization has drifted from the TypeScript one (or vice versa).
+
+use crate::hash::hash_concerns;
+use crate::types::Concern;
+use serde::Deserialize;
+
+const VECTORS_JSON: &str = include_str!("../../tests/fixtures/conformance/hash_concerns.json");
+
+#[derive(Deserialize)]
+struct VectorFile {
+    vectors: Vec<Vector>,
+}
+
+#[derive(Deserialize)]
+struct Vector {
+    name: String,
+    concerns: Vec<Concern>,
+    expected_hash: String,
+}
+
+#[test]
+fn test_hash_concerns_conformance_vectors() {
+    let file: VectorFile = serde_json::from_str(VECTORS_JSON).expect("vector file parses");
+    assert!(!file.vectors.is_empty());
+
+    for vector in &file.vectors {
+        assert_eq!(
+            hash_concerns(&vector.concerns),
+            vector.expected_hash,
+            "conformance vector `{}`",
+            vector.name
+        );
+    }
+}
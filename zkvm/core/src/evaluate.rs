@@ -0,0 +1,294 @@
+//! The guest's derivation from input to journal output.
+//!
+//! Shared by the single and batch guest programs, and by the host's dry-run
+//! `derive_output`, so all three commit or report exactly the same output.
+//! `hash_concerns_from_json` reuses the same steps for verifiers that only
+//! need to recompute `concerns_hash`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::hash::{hash_assumptions, hash_concerns_with_max_evidence, hash_raw_analysis, HashAlgo};
+#[cfg(feature = "blake3")]
+use crate::hash::hash_concerns_blake3_with_max_evidence;
+use crate::json::extract_json;
+use crate::types::{
+    confidence_to_bps, truncate_evidence_to, AnalysisResponse, Concern, GuestInput, GuestInputBatch,
+    GuestOutput, GuestOutputBatch, ValidationError, Verdict, JOURNAL_SCHEMA_VERSION,
+    MAX_ANALYSIS_JSON_LEN, MAX_CONCERNS,
+};
+#[cfg(feature = "std")]
+use crate::types::MAX_EVIDENCE_LENGTH;
+use crate::verdict::{derive_verdict_with_policy, map_verdict_to_action};
+
+/// Derive the `GuestOutput` the guest commits for `input`.
+///
+/// Steps: extract the JSON from any markdown fences, parse it, truncate
+/// evidence to `input.max_evidence_length` on a char boundary, re-derive the
+/// verdict under `input.policy` (the LLM's stated verdict is NOT trusted),
+/// map it to an action, and hash the concerns with `input.hash_algo`.
+///
+/// Analysis JSON longer than MAX_ANALYSIS_JSON_LEN is refused with
+/// `ValidationError::AnalysisTooLarge` before it is parsed.
+/// Analysis JSON that does not parse yields `Verdict::Malformed` with
+/// `concerns_hash` set to `hash_raw_analysis` of the raw input. Responses
+/// over MAX_CONCERNS are refused with `ValidationError::TooManyConcerns`.
+/// Without the `blake3` feature, `HashAlgo::Blake3` falls back to SHA-256;
+/// the guest always enables it.
+pub fn evaluate(input: &GuestInput) -> Result<GuestOutput, ValidationError> {
+    let Some(mut response) = parse_analysis(&input.analysis_json)? else {
+        return Ok(malformed_output(input));
+    };
+
+    let max_evidence_length = input.max_evidence_length as usize;
+    for concern in response.concerns.iter_mut() {
+        let truncated = truncate_evidence_to(&concern.evidence, max_evidence_length);
+        if truncated.len() < concern.evidence.len() {
+            concern.evidence = truncated.into();
+        }
+    }
+
+    let verdict = derive_verdict_with_policy(&response.concerns, &input.policy);
+    let stated_verdict_matches = response.verdict == verdict.as_str();
+    let action = map_verdict_to_action(&verdict, &response.concerns);
+    let (hash_algo, concerns_hash) = hash_with(&response.concerns, max_evidence_length, input.hash_algo);
+
+    Ok(GuestOutput {
+        schema_version: JOURNAL_SCHEMA_VERSION,
+        verdict,
+        action,
+        confidence_bps: confidence_to_bps(response.confidence),
+        concerns_hash,
+        hash_algo,
+        max_evidence_length: input.max_evidence_length,
+        policy_hash: input.policy.policy_hash(),
+        thinking_hash: input.thinking_hash.clone(),
+        card_hash: input.card_hash.clone(),
+        values_hash: input.values_hash.clone(),
+        model: input.model.clone(),
+        assumptions_hash: hash_assumptions(&input.assumptions),
+        stated_verdict_matches,
+    })
+}
+
+/// Recompute the `concerns_hash` the guest commits for `analysis_json`
+/// under the default evidence length and SHA-256.
+///
+/// See `hash_concerns_from_json_with`.
+#[cfg(feature = "std")]
+pub fn hash_concerns_from_json(analysis_json: &str) -> Result<String, ValidationError> {
+    hash_concerns_from_json_with(analysis_json, MAX_EVIDENCE_LENGTH as u32, HashAlgo::Sha256)
+}
+
+/// Recompute the `concerns_hash` the guest commits for `analysis_json`,
+/// given the journal's `max_evidence_length` and `hash_algo`.
+///
+/// Runs the guest's own pipeline: size check, fence extraction, parse,
+/// evidence truncation and hashing. JSON that does not parse yields
+/// `hash_raw_analysis` of the input, as committed for `Verdict::Malformed`.
+/// Errors are the ones `evaluate` refuses to prove.
+#[cfg(feature = "std")]
+pub fn hash_concerns_from_json_with(
+    analysis_json: &str,
+    max_evidence_length: u32,
+    hash_algo: HashAlgo,
+) -> Result<String, ValidationError> {
+    Ok(match parse_analysis(analysis_json)? {
+        Some(response) => hash_with(&response.concerns, max_evidence_length as usize, hash_algo).1,
+        None => hash_raw_analysis(analysis_json),
+    })
+}
+
+/// Parse the analysis as the guest does: `Ok(None)` if it does not parse,
+/// an error if it is over MAX_ANALYSIS_JSON_LEN or MAX_CONCERNS.
+fn parse_analysis(analysis_json: &str) -> Result<Option<AnalysisResponse>, ValidationError> {
+    if analysis_json.len() > MAX_ANALYSIS_JSON_LEN {
+        return Err(ValidationError::AnalysisTooLarge { len: analysis_json.len() });
+    }
+    let Ok(response) = serde_json::from_str::<AnalysisResponse>(extract_json(analysis_json)) else {
+        return Ok(None);
+    };
+    if response.concerns.len() > MAX_CONCERNS {
+        return Err(ValidationError::TooManyConcerns { count: response.concerns.len() });
+    }
+    Ok(Some(response))
+}
+
+/// Hash `concerns` with `hash_algo`, returning the algorithm actually used.
+/// Without the `blake3` feature, BLAKE3 falls back to SHA-256.
+fn hash_with(concerns: &[Concern], max_evidence_length: usize, hash_algo: HashAlgo) -> (HashAlgo, String) {
+    match hash_algo {
+        #[cfg(feature = "blake3")]
+        HashAlgo::Blake3 => (
+            HashAlgo::Blake3,
+            hash_concerns_blake3_with_max_evidence(concerns, max_evidence_length),
+        ),
+        _ => (
+            HashAlgo::Sha256,
+            hash_concerns_with_max_evidence(concerns, max_evidence_length),
+        ),
+    }
+}
+
+/// `evaluate` each input of a batch, in order, after checking the batch
+/// size with `GuestInputBatch::validate`.
+pub fn evaluate_batch(batch: &GuestInputBatch) -> Result<GuestOutputBatch, ValidationError> {
+    batch.validate()?;
+    let outputs = batch.inputs.iter().map(evaluate).collect::<Result<Vec<_>, _>>()?;
+    Ok(GuestOutputBatch {
+        schema_version: JOURNAL_SCHEMA_VERSION,
+        outputs,
+    })
+}
+
+/// The output committed for analysis JSON that does not parse.
+fn malformed_output(input: &GuestInput) -> GuestOutput {
+    GuestOutput {
+        schema_version: JOURNAL_SCHEMA_VERSION,
+        verdict: Verdict::Malformed,
+        action: map_verdict_to_action(&Verdict::Malformed, &[]),
+        confidence_bps: 0,
+        concerns_hash: hash_raw_analysis(&input.analysis_json),
+        hash_algo: HashAlgo::Sha256,
+        max_evidence_length: input.max_evidence_length,
+        policy_hash: input.policy.policy_hash(),
+        thinking_hash: input.thinking_hash.clone(),
+        card_hash: input.card_hash.clone(),
+        values_hash: input.values_hash.clone(),
+        model: input.model.clone(),
+        assumptions_hash: hash_assumptions(&input.assumptions),
+        stated_verdict_matches: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hash_concerns;
+    use crate::types::{Action, MAX_BATCH_SIZE, MAX_EVIDENCE_LENGTH};
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    const REVIEW_JSON: &str = include_str!("../../tests/fixtures/review_needed.json");
+    const BOUNDARY_JSON: &str = include_str!("../../tests/fixtures/boundary_injection.json");
+
+    fn input(analysis_json: &str) -> GuestInput {
+        GuestInput {
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            analysis_json: analysis_json.to_string(),
+            thinking_hash: "t".to_string(),
+            card_hash: "c".to_string(),
+            values_hash: "v".to_string(),
+            model: "m".to_string(),
+            policy: Default::default(),
+            hash_algo: Default::default(),
+            max_evidence_length: MAX_EVIDENCE_LENGTH as u32,
+            assumptions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_rederives_verdict() {
+        let output = evaluate(&input(REVIEW_JSON)).unwrap();
+        let response: AnalysisResponse = serde_json::from_str(REVIEW_JSON).unwrap();
+        assert_eq!(output.verdict, Verdict::ReviewNeeded);
+        assert_eq!(output.action, Action::LogAndContinue);
+        assert_eq!(output.concerns_hash, hash_concerns(&response.concerns));
+        assert_eq!(output.confidence_bps, confidence_to_bps(response.confidence));
+        assert_eq!(output.thinking_hash, "t");
+        assert!(output.stated_verdict_matches);
+    }
+
+    #[test]
+    fn test_evaluate_flags_misstated_verdict() {
+        let lying = REVIEW_JSON.replacen("\"review_needed\"", "\"clear\"", 1);
+        let output = evaluate(&input(&lying)).unwrap();
+        assert_eq!(output.verdict, Verdict::ReviewNeeded);
+        assert!(!output.stated_verdict_matches);
+    }
+
+    #[test]
+    fn test_evaluate_unparseable_is_malformed() {
+        let garbage = "I can't produce JSON for that.";
+        let output = evaluate(&input(garbage)).unwrap();
+        assert_eq!(output.verdict, Verdict::Malformed);
+        assert_eq!(output.action, Action::PauseForReview);
+        assert_eq!(output.concerns_hash, hash_raw_analysis(garbage));
+        assert_eq!(output.confidence_bps, 0);
+        assert!(!output.stated_verdict_matches);
+    }
+
+    #[test]
+    fn test_evaluate_refuses_too_many_concerns() {
+        let concern = r#"{"category": "value_misalignment", "severity": "low", "description": "d"}"#;
+        let json = alloc::format!(
+            r#"{{"verdict": "clear", "concerns": [{}], "confidence": 0.5, "reasoning_summary": "s"}}"#,
+            vec![concern; MAX_CONCERNS + 1].join(",")
+        );
+        assert_eq!(
+            evaluate(&input(&json)).unwrap_err(),
+            ValidationError::TooManyConcerns { count: MAX_CONCERNS + 1 }
+        );
+    }
+
+    #[test]
+    fn test_evaluate_refuses_oversized_analysis() {
+        // Checked before parsing, so even unparseable input is refused
+        // rather than committed as Malformed.
+        let at_limit = "x".repeat(MAX_ANALYSIS_JSON_LEN);
+        assert_eq!(evaluate(&input(&at_limit)).unwrap().verdict, Verdict::Malformed);
+
+        let over = "x".repeat(MAX_ANALYSIS_JSON_LEN + 1);
+        assert_eq!(
+            evaluate(&input(&over)).unwrap_err(),
+            ValidationError::AnalysisTooLarge { len: MAX_ANALYSIS_JSON_LEN + 1 }
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hash_concerns_from_json_matches_evaluate() {
+        let fenced = alloc::format!("```json\n{}\n```", REVIEW_JSON);
+        for json in [REVIEW_JSON, BOUNDARY_JSON, fenced.as_str(), "not json"] {
+            assert_eq!(hash_concerns_from_json(json).unwrap(), evaluate(&input(json)).unwrap().concerns_hash);
+        }
+
+        let mut short = input(BOUNDARY_JSON);
+        short.max_evidence_length = 10;
+        short.hash_algo = HashAlgo::Blake3;
+        let output = evaluate(&short).unwrap();
+        assert_eq!(
+            hash_concerns_from_json_with(BOUNDARY_JSON, 10, output.hash_algo).unwrap(),
+            output.concerns_hash
+        );
+
+        let over = "x".repeat(MAX_ANALYSIS_JSON_LEN + 1);
+        assert!(matches!(hash_concerns_from_json(&over), Err(ValidationError::AnalysisTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_evaluate_batch_keeps_input_order() {
+        let batch = GuestInputBatch {
+            inputs: vec![input(BOUNDARY_JSON), input("not json"), input(REVIEW_JSON)],
+        };
+        let output = evaluate_batch(&batch).unwrap();
+        assert_eq!(output.schema_version, JOURNAL_SCHEMA_VERSION);
+        let verdicts: Vec<Verdict> = output.outputs.iter().map(|o| o.verdict.clone()).collect();
+        assert_eq!(verdicts, [Verdict::BoundaryViolation, Verdict::Malformed, Verdict::ReviewNeeded]);
+        for (item, single) in output.outputs.iter().zip(&batch.inputs) {
+            assert_eq!(item, &evaluate(single).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_evaluate_batch_size_limits() {
+        let empty = GuestInputBatch { inputs: Vec::new() };
+        assert_eq!(evaluate_batch(&empty).unwrap_err(), ValidationError::BatchSize { count: 0 });
+
+        let full = GuestInputBatch { inputs: vec![input(REVIEW_JSON); MAX_BATCH_SIZE] };
+        assert_eq!(evaluate_batch(&full).unwrap().outputs.len(), MAX_BATCH_SIZE);
+
+        let over = GuestInputBatch { inputs: vec![input(REVIEW_JSON); MAX_BATCH_SIZE + 1] };
+        assert!(matches!(evaluate_batch(&over), Err(ValidationError::BatchSize { .. })));
+    }
+}
@@ -1,63 +1,211 @@
 //! Concern hashing for cross-language conformance.
 
-use alloc::string::{String, ToString};
+use alloc::string::String;
 use alloc::vec::Vec;
 use sha2::{Sha256, Digest};
-use crate::types::{Concern, MAX_EVIDENCE_LENGTH};
-use serde::Serialize;
-
-/// Normalized concern for hashing — evidence truncated to MAX_EVIDENCE_LENGTH.
-#[derive(Serialize)]
-struct NormalizedConcern {
-    category: String,
-    severity: String,
-    description: String,
-    evidence: String,
+use crate::types::{truncate_evidence_to, Assumption, Concern, ValidationError, MAX_EVIDENCE_LENGTH};
+use serde::{Deserialize, Serialize};
+
+/// Algorithm used to produce `concerns_hash`.
+///
+/// Committed to the journal so verifiers know how to recompute the hash.
+/// SHA-256 is the default and the only algorithm the TypeScript side uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+/// Normalized concern for hashing — evidence truncated to the length limit.
+///
+/// Fields are declared in lexicographic order. `serde_json` emits struct
+/// fields in declaration order, so this ordering IS the canonical sorted-key
+/// form (`JSON.stringify` with sorted keys on the TypeScript side). Do not
+/// reorder.
+#[derive(Serialize, PartialEq)]
+struct NormalizedConcern<'a> {
+    category: &'a str,
+    description: &'a str,
+    evidence: &'a str,
+    severity: &'a str,
 }
 
 /// Hash a list of concerns into a deterministic SHA-256 hex string.
 ///
 /// Process:
-/// 1. Truncate evidence to MAX_EVIDENCE_LENGTH chars
-/// 2. Serialize each concern as JSON with sorted keys
-/// 3. Concatenate all concern JSONs
-/// 4. SHA-256 hash the concatenation
+/// 1. Truncate evidence to MAX_EVIDENCE_LENGTH bytes, backing off to a char
+///    boundary (`truncate_evidence`)
+/// 2. Serialize the concerns as a compact JSON array, each object with keys
+///    in sorted order: `category`, `description`, `evidence`, `severity`
+/// 3. SHA-256 hash the UTF-8 bytes of that JSON
+///
+/// The JSON is streamed into the hasher one concern at a time (see
+/// `stream_canonical_json`), so memory stays at one concern's worth however
+/// long the array is; the digest is identical to hashing the whole string.
+///
+/// Duplicates are NOT removed: two identical concerns are hashed twice, as
+/// the TypeScript side does. This is the hash committed to the journal.
+/// Tools that dedupe concerns before hashing should compare against
+/// `hash_concerns_dedup` instead.
+///
+/// The TypeScript implementation truncates to 200 UTF-16 code units instead
+/// of bytes. The two agree on ASCII evidence and on evidence short enough to
+/// be kept whole; longer non-ASCII evidence hashes differently (the
+/// conformance fixture keeps its truncation vectors ASCII-only for this
+/// reason).
+///
+/// Order matters: the same concerns in another order hash differently.
+/// This stays the committed contract, since changing it would change every
+/// existing `concerns_hash`; consumers with set semantics compare against
+/// `hash_concerns_sorted`.
 pub fn hash_concerns(concerns: &[Concern]) -> String {
-    let normalized: Vec<NormalizedConcern> = concerns
-        .iter()
-        .map(|c| {
-            let evidence = if c.evidence.len() > MAX_EVIDENCE_LENGTH {
-                c.evidence[..MAX_EVIDENCE_LENGTH].to_string()
-            } else {
-                c.evidence.clone()
-            };
-
-            // Serialize category/severity to their JSON string values
-            let category = serde_json::to_string(&c.category)
-                .unwrap_or_default()
-                .trim_matches('"')
-                .to_string();
-            let severity = serde_json::to_string(&c.severity)
-                .unwrap_or_default()
-                .trim_matches('"')
-                .to_string();
-
-            NormalizedConcern {
-                category,
-                severity,
-                description: c.description.clone(),
-                evidence,
-            }
-        })
-        .collect();
-
-    let json = serde_json::to_string(&normalized).unwrap_or_default();
+    hash_concerns_with_max_evidence(concerns, MAX_EVIDENCE_LENGTH)
+}
+
+/// Like `hash_concerns`, truncating evidence to `max_evidence_length` bytes
+/// instead of MAX_EVIDENCE_LENGTH. The guest uses this with
+/// `GuestInput::max_evidence_length` and commits the length alongside the hash.
+pub fn hash_concerns_with_max_evidence(concerns: &[Concern], max_evidence_length: usize) -> String {
+    let mut hasher = Sha256::new();
+    stream_canonical_json(concerns, max_evidence_length, |bytes| hasher.update(bytes));
+    hex::encode(hasher.finalize())
+}
+
+/// SHA-256 hex of the raw analysis JSON bytes, exactly as given to the
+/// guest. Committed as `concerns_hash` for `Verdict::Malformed`, binding the
+/// proof to the input that failed to parse.
+pub fn hash_raw_analysis(analysis_json: &str) -> String {
+    sha256_hex(analysis_json)
+}
+
+/// Hex SHA-256 identifying the assumptions a proof composed with, committed
+/// as `assumptions_hash`. Empty when there are none.
+///
+/// Hashes, for each assumption in order, its image ID as eight little-endian
+/// `u32`s followed by the SHA-256 of its journal, so a verifier holding the
+/// upstream receipts can recompute it.
+pub fn hash_assumptions(assumptions: &[Assumption]) -> String {
+    if assumptions.is_empty() {
+        return String::new();
+    }
+    let mut hasher = Sha256::new();
+    for assumption in assumptions {
+        for word in assumption.image_id {
+            hasher.update(word.to_le_bytes());
+        }
+        hasher.update(Sha256::digest(&assumption.journal));
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Like `hash_concerns`, but with exact duplicates removed first.
+///
+/// Dedup policy: two concerns are duplicates when their normalized forms
+/// (all four fields, evidence truncated to MAX_EVIDENCE_LENGTH) are equal.
+/// The first occurrence is kept and the original order is otherwise
+/// preserved, so concerns differing only past the evidence limit collapse
+/// to one. Never committed to the journal; it exists so deduping consumers
+/// have one agreed definition to match.
+pub fn hash_concerns_dedup(concerns: &[Concern]) -> String {
+    let mut unique: Vec<NormalizedConcern> = Vec::with_capacity(concerns.len());
+    for concern in concerns.iter().map(|c| normalize(c, MAX_EVIDENCE_LENGTH)) {
+        if !unique.contains(&concern) {
+            unique.push(concern);
+        }
+    }
+    sha256_hex(&serde_json::to_string(&unique).unwrap_or_default())
+}
+
+/// Like `hash_concerns`, but independent of the order of `concerns`.
+///
+/// Concerns are sorted before hashing by, in order: category name (as
+/// serialized, bytewise), severity rank (low < medium < high < critical),
+/// description (bytewise), then evidence truncated to MAX_EVIDENCE_LENGTH
+/// (bytewise). Every normalized field is in the key, so any permutation of
+/// the same concerns sorts identically. Duplicates are kept. Never committed
+/// to the journal; the guest and host both commit `hash_concerns`.
+pub fn hash_concerns_sorted(concerns: &[Concern]) -> String {
+    fn sort_key(c: &Concern) -> (&str, u8, &str, &str) {
+        (
+            c.category.as_str(),
+            c.severity.as_u8(),
+            &c.description,
+            truncate_evidence_to(&c.evidence, MAX_EVIDENCE_LENGTH),
+        )
+    }
+    let mut sorted = concerns.to_vec();
+    sorted.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+    hash_concerns(&sorted)
+}
+
+/// Check a pass-through commitment (`thinking_hash`, `card_hash`,
+/// `values_hash`) before it is committed: exactly 64 hex characters, as
+/// produced by `sha256_hex`. Empty is allowed and means no commitment.
+pub fn validate_hash_hex(hash: &str) -> Result<(), ValidationError> {
+    if hash.is_empty() || (hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())) {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidHashHex { len: hash.chars().count() })
+    }
+}
+
+fn sha256_hex(text: &str) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(json.as_bytes());
+    hasher.update(text.as_bytes());
     let result = hasher.finalize();
     hex::encode(result)
 }
 
+/// Hash a list of concerns with BLAKE3 instead of SHA-256.
+///
+/// Same normalization as `hash_concerns`; only the digest differs. Cheaper
+/// in the guest, but not reproducible by verifiers that only know SHA-256.
+#[cfg(feature = "blake3")]
+pub fn hash_concerns_blake3(concerns: &[Concern]) -> String {
+    hash_concerns_blake3_with_max_evidence(concerns, MAX_EVIDENCE_LENGTH)
+}
+
+/// BLAKE3 counterpart of `hash_concerns_with_max_evidence`.
+#[cfg(feature = "blake3")]
+pub fn hash_concerns_blake3_with_max_evidence(concerns: &[Concern], max_evidence_length: usize) -> String {
+    let mut hasher = blake3::Hasher::new();
+    stream_canonical_json(concerns, max_evidence_length, |bytes| {
+        hasher.update(bytes);
+    });
+    hex::encode(hasher.finalize().as_bytes())
+}
+
+/// Normalize one concern for hashing. Category and severity become their
+/// serde string values.
+fn normalize(concern: &Concern, max_evidence_length: usize) -> NormalizedConcern<'_> {
+    NormalizedConcern {
+        category: concern.category.as_str(),
+        description: &concern.description,
+        evidence: truncate_evidence_to(&concern.evidence, max_evidence_length),
+        severity: concern.severity.as_str(),
+    }
+}
+
+/// Feed the canonical JSON of the normalized concerns to `update` in pieces.
+///
+/// The concatenated pieces are byte-for-byte `serde_json::to_string` of the
+/// whole normalized array: `[`, each object separated by `,` with no
+/// whitespace, then `]`. Only one concern's JSON is held at a time.
+fn stream_canonical_json(concerns: &[Concern], max_evidence_length: usize, mut update: impl FnMut(&[u8])) {
+    update(b"[");
+    for (index, concern) in concerns.iter().enumerate() {
+        if index > 0 {
+            update(b",");
+        }
+        let json = serde_json::to_string(&normalize(concern, max_evidence_length)).unwrap_or_default();
+        update(json.as_bytes());
+    }
+    update(b"]");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,6 +218,41 @@ mod tests {
         // SHA-256 of "[]"
         assert!(!hash.is_empty());
         assert_eq!(hash.len(), 64); // SHA-256 hex is 64 chars
+        assert_eq!(hash, "4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945");
+    }
+
+    #[test]
+    fn test_hash_pinned_sorted_keys() {
+        // Pinned against an independent sorted-key serialization:
+        // [{"category":"prompt_injection","description":"Test injection attempt",
+        //   "evidence":"suspicious content","severity":"critical"}]
+        let concerns = vec![Concern {
+            category: ConcernCategory::PromptInjection,
+            severity: Severity::Critical,
+            description: "Test injection attempt".to_string(),
+            evidence: "suspicious content".to_string(),
+        }];
+        assert_eq!(
+            hash_concerns(&concerns),
+            "d2cffef25ee3c51462c4f3dbce65b6b6d0e8fa69aa2ec206bc4c5c237a286b36"
+        );
+    }
+
+    #[test]
+    fn test_hash_unknown_category_pinned() {
+        // Unmodeled categories hash verbatim, so the hash binds the name:
+        // [{"category":"data_exfiltration","description":"d","evidence":"","severity":"high"}]
+        let parse = |category: &str| -> Vec<Concern> {
+            let json = alloc::format!(
+                r#"[{{"category": "{}", "severity": "high", "description": "d"}}]"#,
+                category
+            );
+            serde_json::from_str(&json).unwrap()
+        };
+        let hash = hash_concerns(&parse("data_exfiltration"));
+        assert_ne!(hash, hash_concerns(&parse("tool_misuse")));
+        assert_ne!(hash, hash_concerns(&parse("unknown")));
+        assert_eq!(hash, "fa3c9473ff2b9d316b58d936b86f9387b2fa29d5705efeb7280ee7a23699e66f");
     }
 
     #[test]
@@ -85,6 +268,163 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_evidence_truncation_multibyte() {
+        // Byte 200 falls inside the emoji; hashing must not panic.
+        let concerns = vec![Concern {
+            category: ConcernCategory::DeceptiveReasoning,
+            severity: Severity::Medium,
+            description: "test".to_string(),
+            evidence: "a".repeat(198) + "\u{1F600}",
+        }];
+        assert_eq!(hash_concerns(&concerns).len(), 64);
+    }
+
+    #[test]
+    fn test_duplicate_concerns() {
+        let concern = Concern {
+            category: ConcernCategory::PromptInjection,
+            severity: Severity::Critical,
+            description: "Test injection attempt".to_string(),
+            evidence: "suspicious content".to_string(),
+        };
+        let single = vec![concern.clone()];
+        let doubled = vec![concern.clone(), concern.clone()];
+
+        // hash_concerns keeps both copies, like the TypeScript side:
+        // [{...},{...}] with the object from test_hash_pinned_sorted_keys twice.
+        assert_eq!(
+            hash_concerns(&doubled),
+            "f91ea77028458b4e84b7a41759bc982300adde0e268a42269f2b44e77ccf0736"
+        );
+        assert_eq!(hash_concerns_dedup(&doubled), hash_concerns(&single));
+
+        // Duplicates after normalization collapse; the first occurrence's
+        // position is kept.
+        let other = Concern {
+            category: ConcernCategory::AutonomyViolation,
+            severity: Severity::Low,
+            description: "other".to_string(),
+            evidence: "x".repeat(300),
+        };
+        let past_limit = Concern { evidence: "x".repeat(400), ..other.clone() };
+        let mixed = vec![other.clone(), concern.clone(), past_limit, concern.clone()];
+        assert_eq!(hash_concerns_dedup(&mixed), hash_concerns(&[other, concern]));
+    }
+
+    #[test]
+    fn test_hash_assumptions() {
+        assert_eq!(hash_assumptions(&[]), "");
+
+        let a = Assumption { image_id: [1; 8], journal: vec![1, 2, 3] };
+        let b = Assumption { image_id: [2; 8], journal: vec![] };
+        let hash = hash_assumptions(&[a.clone(), b.clone()]);
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, hash_assumptions(&[b.clone(), a.clone()]));
+        let other_journal = Assumption { journal: vec![1, 2, 4], ..a.clone() };
+        assert_ne!(hash, hash_assumptions(&[other_journal, b]));
+    }
+
+    #[test]
+    fn test_validate_hash_hex() {
+        assert!(validate_hash_hex("").is_ok());
+        assert!(validate_hash_hex(&hash_concerns(&[])).is_ok());
+        assert!(validate_hash_hex(&"AB".repeat(32)).is_ok());
+        assert_eq!(validate_hash_hex("abc"), Err(ValidationError::InvalidHashHex { len: 3 }));
+        assert_eq!(
+            validate_hash_hex(&"g".repeat(64)),
+            Err(ValidationError::InvalidHashHex { len: 64 })
+        );
+        assert!(validate_hash_hex(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_hash_sorted_ignores_order() {
+        let concern = |category, severity, description: &str| Concern {
+            category,
+            severity,
+            description: description.to_string(),
+            evidence: String::new(),
+        };
+        let a = concern(ConcernCategory::PromptInjection, Severity::High, "a");
+        let b = concern(ConcernCategory::AutonomyViolation, Severity::Low, "b");
+        let c = concern(ConcernCategory::AutonomyViolation, Severity::Critical, "c");
+        let d = concern(ConcernCategory::AutonomyViolation, Severity::Critical, "d");
+
+        let forward = [a.clone(), b.clone(), c.clone(), d.clone()];
+        let reversed = [d.clone(), c.clone(), b.clone(), a.clone()];
+        let shuffled = [c.clone(), a.clone(), d.clone(), b.clone()];
+        assert_ne!(hash_concerns(&forward), hash_concerns(&reversed));
+        for permutation in [&reversed, &shuffled] {
+            assert_eq!(hash_concerns_sorted(permutation), hash_concerns_sorted(&forward));
+        }
+
+        // Sorted by category, then severity rank (not name), then description.
+        assert_eq!(hash_concerns_sorted(&forward), hash_concerns(&[b, c, d, a]));
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn test_hash_blake3_differs_from_sha256() {
+        let concerns = vec![Concern {
+            category: ConcernCategory::PromptInjection,
+            severity: Severity::Critical,
+            description: "Test injection attempt".to_string(),
+            evidence: "suspicious content".to_string(),
+        }];
+        let hash = hash_concerns_blake3(&concerns);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_concerns_blake3(&concerns));
+        assert_ne!(hash, hash_concerns(&concerns));
+    }
+
+    #[test]
+    fn test_streamed_json_matches_one_shot() {
+        let concerns = [
+            Concern {
+                category: ConcernCategory::PromptInjection,
+                severity: Severity::Critical,
+                description: "quote \" and newline \n".to_string(),
+                evidence: "a".repeat(198) + "\u{1F600}",
+            },
+            Concern {
+                category: ConcernCategory::UndeclaredIntent,
+                severity: Severity::Low,
+                description: "second".to_string(),
+                evidence: String::new(),
+            },
+        ];
+
+        for list in [&concerns[..0], &concerns[..1], &concerns[..]] {
+            let mut streamed = Vec::new();
+            stream_canonical_json(list, MAX_EVIDENCE_LENGTH, |bytes| streamed.extend_from_slice(bytes));
+
+            let normalized: Vec<NormalizedConcern> =
+                list.iter().map(|c| normalize(c, MAX_EVIDENCE_LENGTH)).collect();
+            let one_shot = serde_json::to_string(&normalized).unwrap();
+            assert_eq!(streamed, one_shot.as_bytes());
+            assert_eq!(hash_concerns(list), sha256_hex(&one_shot));
+        }
+    }
+
+    #[test]
+    fn test_hash_with_max_evidence() {
+        let concern = |evidence: String| Concern {
+            category: ConcernCategory::PromptInjection,
+            severity: Severity::High,
+            description: "test".to_string(),
+            evidence,
+        };
+        let long = vec![concern("x".repeat(500))];
+
+        assert_eq!(hash_concerns_with_max_evidence(&long, MAX_EVIDENCE_LENGTH), hash_concerns(&long));
+        assert_ne!(hash_concerns_with_max_evidence(&long, 300), hash_concerns(&long));
+        assert_eq!(
+            hash_concerns_with_max_evidence(&long, 300),
+            hash_concerns_with_max_evidence(&[concern("x".repeat(300))], 1_000)
+        );
+    }
+
     #[test]
     fn test_evidence_truncation() {
         let long_evidence = "x".repeat(500);
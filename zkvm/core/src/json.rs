@@ -0,0 +1,88 @@
+//! Extraction of the analysis JSON object from raw LLM output.
+
+/// Extract the first complete JSON object from `input`.
+///
+/// LLM output may wrap the JSON in markdown fences or surround it with prose.
+/// Starting at the first `{`, this scans with a brace-depth counter that
+/// ignores braces inside string literals (honoring `\` escapes) and returns
+/// the first balanced object. Trailing text — including stray `}` — is
+/// ignored. If no balanced object is found, `input` is returned unchanged so
+/// the parser reports the error.
+pub fn extract_json(input: &str) -> &str {
+    let start = match input.find('{') {
+        Some(start) => start,
+        None => return input,
+    };
+
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (offset, byte) in input.as_bytes()[start..].iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &input[start..=start + offset];
+                }
+            }
+            _ => {}
+        }
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_plain_object() {
+        assert_eq!(extract_json(r#"{"a":1}"#), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_extract_from_markdown_fence_with_trailing_brace() {
+        let input = "```json\n{\"verdict\":\"clear\",\"concerns\":[]}\n```\nextra }";
+        assert_eq!(extract_json(input), r#"{"verdict":"clear","concerns":[]}"#);
+    }
+
+    #[test]
+    fn test_extract_braces_inside_strings() {
+        let input = r#"{"reasoning_summary":"saw { and } in text","n":{"x":"}"}} trailing"#;
+        assert_eq!(
+            extract_json(input),
+            r#"{"reasoning_summary":"saw { and } in text","n":{"x":"}"}}"#
+        );
+    }
+
+    #[test]
+    fn test_extract_escaped_quote_in_string() {
+        let input = r#"prefix {"evidence":"he said \"}\" then left"} suffix"#;
+        assert_eq!(extract_json(input), r#"{"evidence":"he said \"}\" then left"}"#);
+    }
+
+    #[test]
+    fn test_extract_first_of_two_objects() {
+        assert_eq!(extract_json(r#"{"a":1} and {"b":2}"#), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_extract_no_object_returns_input() {
+        assert_eq!(extract_json("no json here"), "no json here");
+        assert_eq!(extract_json(r#"{"unterminated": "#), r#"{"unterminated": "#);
+    }
+}
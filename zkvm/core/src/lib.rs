@@ -10,7 +10,16 @@ extern crate alloc;
 pub mod types;
 pub mod verdict;
 pub mod hash;
+pub mod json;
+pub mod evaluate;
+#[cfg(feature = "schema")]
+pub mod schema;
+
+#[cfg(all(test, feature = "std"))]
+mod conformance;
 
 pub use types::*;
 pub use verdict::*;
 pub use hash::*;
+pub use json::*;
+pub use evaluate::*;
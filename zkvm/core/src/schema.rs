@@ -0,0 +1,56 @@
+//! JSON Schemas for the proof journal and the analysis input.
+//!
+//! The Rust types are the source of truth; the schemas committed under
+//! `zkvm/schemas/` are generated from them (`aip-prover schema`) for
+//! TypeScript codegen, and a test fails when the two drift apart.
+
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+use crate::types::{AnalysisResponse, GuestOutput};
+
+/// JSON Schema of `GuestOutput`, as decoded from a journal.
+pub fn guest_output_schema() -> RootSchema {
+    schema_for!(GuestOutput)
+}
+
+/// JSON Schema of `AnalysisResponse`, the analysis JSON the guest parses.
+pub fn analysis_response_schema() -> RootSchema {
+    schema_for!(AnalysisResponse)
+}
+
+/// Pretty-printed schema with a trailing newline, the form committed under
+/// `zkvm/schemas/`.
+pub fn to_pretty_json(schema: &RootSchema) -> String {
+    let mut json = serde_json::to_string_pretty(schema).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_committed_schemas_are_current() {
+        // Regenerate with `aip-prover schema guest-output` / `analysis-response`.
+        assert_eq!(
+            to_pretty_json(&guest_output_schema()),
+            include_str!("../../schemas/guest_output.schema.json")
+        );
+        assert_eq!(
+            to_pretty_json(&analysis_response_schema()),
+            include_str!("../../schemas/analysis_response.schema.json")
+        );
+    }
+
+    #[test]
+    fn test_guest_output_schema_lists_journal_fields() {
+        let schema = serde_json::to_value(guest_output_schema()).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+        for field in ["schema_version", "verdict", "action", "confidence_bps", "concerns_hash", "model"] {
+            assert!(properties.contains_key(field), "missing {field}");
+        }
+        assert!(schema["definitions"]["Verdict"].to_string().contains("malformed"));
+    }
+}
@@ -7,13 +7,20 @@
 
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
 
+use crate::hash::HashAlgo;
+use crate::verdict::VerdictPolicy;
+
 /// Concern categories from the AIP specification.
 /// Maps to TypeScript: "prompt_injection" | "value_misalignment" | "autonomy_violation"
 ///   | "reasoning_corruption" | "deceptive_reasoning" | "undeclared_intent"
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+///
+/// Serialized as its snake_case name; serde is implemented by hand so that
+/// `Unknown` round-trips its original string.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConcernCategory {
     PromptInjection,
     ValueMisalignment,
@@ -21,10 +28,89 @@ pub enum ConcernCategory {
     ReasoningCorruption,
     DeceptiveReasoning,
     UndeclaredIntent,
+    /// Any category this build doesn't model (e.g. `data_exfiltration`), so a
+    /// new category from the LLM doesn't fail the whole parse. The original
+    /// string is kept, and serializes and hashes verbatim, as on the
+    /// TypeScript side. Category rules never match it, so it only escalates
+    /// on severity (see `derive_verdict`).
+    Unknown(String),
+}
+
+impl Serialize for ConcernCategory {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ConcernCategory {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CategoryVisitor;
+
+        impl serde::de::Visitor<'_> for CategoryVisitor {
+            type Value = ConcernCategory;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a concern category string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ConcernCategory, E> {
+                Ok(ConcernCategory::from_name(value))
+            }
+        }
+
+        deserializer.deserialize_str(CategoryVisitor)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for ConcernCategory {
+    fn schema_name() -> String {
+        "ConcernCategory".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, Metadata, SchemaObject, SubschemaValidation};
+
+        let described = |description: &str| {
+            Some(alloc::boxed::Box::new(Metadata {
+                description: Some(description.into()),
+                ..Default::default()
+            }))
+        };
+        let known = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(ConcernCategory::MODELED.iter().map(|c| c.as_str().into()).collect()),
+            ..Default::default()
+        };
+        let unknown = SchemaObject {
+            metadata: described(
+                "Any category this build doesn't model (e.g. `data_exfiltration`), so a new category from \
+                 the LLM doesn't fail the whole parse. The original string is kept, and serializes and \
+                 hashes verbatim, as on the TypeScript side. Category rules never match it, so it only \
+                 escalates on severity (see `derive_verdict`).",
+            ),
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        };
+        SchemaObject {
+            metadata: described(
+                "Concern categories from the AIP specification. Maps to TypeScript: \"prompt_injection\" | \
+                 \"value_misalignment\" | \"autonomy_violation\" | \"reasoning_corruption\" | \
+                 \"deceptive_reasoning\" | \"undeclared_intent\"",
+            ),
+            subschemas: Some(alloc::boxed::Box::new(SubschemaValidation {
+                one_of: Some(alloc::vec![known.into(), unknown.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
 }
 
 /// Severity levels with total ordering: low < medium < high < critical
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Low,
@@ -34,7 +120,10 @@ pub enum Severity {
 }
 
 /// A single concern raised during integrity analysis.
+///
+/// Every key is a single word, so snake_case and camelCase JSON agree.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct Concern {
     pub category: ConcernCategory,
@@ -46,15 +135,21 @@ pub struct Concern {
 
 /// Integrity verdict — the conclusion of the analysis.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Verdict {
     Clear,
     ReviewNeeded,
     BoundaryViolation,
+    /// The analysis JSON did not parse. Committed by the guest in place of a
+    /// derived verdict, so "the LLM returned garbage" is itself provable;
+    /// `derive_verdict` never returns it.
+    Malformed,
 }
 
 /// Recommended action based on verdict + concern severity.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Action {
     Continue,
@@ -65,20 +160,371 @@ pub enum Action {
 
 /// The LLM analysis response JSON structure.
 /// This is what the guest program parses and re-evaluates.
+///
+/// Some providers return camelCase keys (`reasoningSummary`); they are
+/// accepted as aliases and always serialize back as snake_case.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnalysisResponse {
-    pub verdict: String, // We don't trust this — we re-derive it
+    pub verdict: String, // We don't trust this — we re-derive it and commit whether it agreed
     pub concerns: Vec<Concern>,
     pub confidence: f64,
+    #[serde(alias = "reasoningSummary")]
     pub reasoning_summary: String,
 }
 
-/// Maximum evidence length (mirrors TypeScript MAX_EVIDENCE_LENGTH).
+/// Error returned when a string is not a valid enum value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    /// Name of the enum that failed to parse (e.g. "Verdict")
+    pub type_name: &'static str,
+    /// The rejected input
+    pub value: String,
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} value: {:?}", self.type_name, self.value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseEnumError {}
+
+fn parse_error(type_name: &'static str, value: &str) -> ParseEnumError {
+    ParseEnumError { type_name, value: value.into() }
+}
+
+// String forms below match the serde rename rules on each enum exactly.
+
+impl ConcernCategory {
+    /// Every category this build models, in declaration order.
+    pub const MODELED: [ConcernCategory; 6] = [
+        ConcernCategory::PromptInjection,
+        ConcernCategory::ValueMisalignment,
+        ConcernCategory::AutonomyViolation,
+        ConcernCategory::ReasoningCorruption,
+        ConcernCategory::DeceptiveReasoning,
+        ConcernCategory::UndeclaredIntent,
+    ];
+
+    /// The serialized name; for `Unknown`, the original string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ConcernCategory::PromptInjection => "prompt_injection",
+            ConcernCategory::ValueMisalignment => "value_misalignment",
+            ConcernCategory::AutonomyViolation => "autonomy_violation",
+            ConcernCategory::ReasoningCorruption => "reasoning_corruption",
+            ConcernCategory::DeceptiveReasoning => "deceptive_reasoning",
+            ConcernCategory::UndeclaredIntent => "undeclared_intent",
+            ConcernCategory::Unknown(name) => name,
+        }
+    }
+
+    /// The category named `name`, or `Unknown` holding it. This is how serde
+    /// reads categories; `FromStr` is strict instead.
+    pub fn from_name(name: &str) -> ConcernCategory {
+        ConcernCategory::MODELED
+            .into_iter()
+            .find(|category| category.as_str() == name)
+            .unwrap_or_else(|| ConcernCategory::Unknown(name.into()))
+    }
+}
+
+impl fmt::Display for ConcernCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ConcernCategory {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prompt_injection" => Ok(ConcernCategory::PromptInjection),
+            "value_misalignment" => Ok(ConcernCategory::ValueMisalignment),
+            "autonomy_violation" => Ok(ConcernCategory::AutonomyViolation),
+            "reasoning_corruption" => Ok(ConcernCategory::ReasoningCorruption),
+            "deceptive_reasoning" => Ok(ConcernCategory::DeceptiveReasoning),
+            "undeclared_intent" => Ok(ConcernCategory::UndeclaredIntent),
+            _ => Err(parse_error("ConcernCategory", s)),
+        }
+    }
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// Stable numeric rank: low=0, medium=1, high=2, critical=3.
+    /// Consistent with the derived `Ord`.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Severity::Low => 0,
+            Severity::Medium => 1,
+            Severity::High => 2,
+            Severity::Critical => 3,
+        }
+    }
+
+    /// Inverse of `as_u8`; `None` for values above 3.
+    pub fn from_u8(value: u8) -> Option<Severity> {
+        match value {
+            0 => Some(Severity::Low),
+            1 => Some(Severity::Medium),
+            2 => Some(Severity::High),
+            3 => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(parse_error("Severity", s)),
+        }
+    }
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Clear => "clear",
+            Verdict::ReviewNeeded => "review_needed",
+            Verdict::BoundaryViolation => "boundary_violation",
+            Verdict::Malformed => "malformed",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clear" => Ok(Verdict::Clear),
+            "review_needed" => Ok(Verdict::ReviewNeeded),
+            "boundary_violation" => Ok(Verdict::BoundaryViolation),
+            "malformed" => Ok(Verdict::Malformed),
+            _ => Err(parse_error("Verdict", s)),
+        }
+    }
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Continue => "continue",
+            Action::LogAndContinue => "log_and_continue",
+            Action::PauseForReview => "pause_for_review",
+            Action::DenyAndEscalate => "deny_and_escalate",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "continue" => Ok(Action::Continue),
+            "log_and_continue" => Ok(Action::LogAndContinue),
+            "pause_for_review" => Ok(Action::PauseForReview),
+            "deny_and_escalate" => Ok(Action::DenyAndEscalate),
+            _ => Err(parse_error("Action", s)),
+        }
+    }
+}
+
+/// Reason an `AnalysisResponse` (or a `GuestInputBatch`) failed validation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// Confidence is NaN or outside [0, 1]
+    ConfidenceOutOfRange(f64),
+    /// The concern at this index has an empty (or whitespace-only) description
+    EmptyDescription { index: usize },
+    /// More than `MAX_CONCERNS` concerns
+    TooManyConcerns { count: usize },
+    /// A batch with no inputs, or more than `MAX_BATCH_SIZE`
+    BatchSize { count: usize },
+    /// `analysis_json` is longer than `MAX_ANALYSIS_JSON_LEN` bytes
+    AnalysisTooLarge { len: usize },
+    /// A commitment hash is neither empty nor 64 hex characters
+    InvalidHashHex { len: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::ConfidenceOutOfRange(c) => {
+                write!(f, "confidence {} is outside [0, 1]", c)
+            }
+            ValidationError::EmptyDescription { index } => {
+                write!(f, "concern {} has an empty description", index)
+            }
+            ValidationError::TooManyConcerns { count } => {
+                write!(f, "{} concerns exceeds the maximum of {}", count, MAX_CONCERNS)
+            }
+            ValidationError::BatchSize { count } => {
+                write!(f, "batch of {} inputs is outside 1..={}", count, MAX_BATCH_SIZE)
+            }
+            ValidationError::AnalysisTooLarge { len } => {
+                write!(
+                    f,
+                    "analysis JSON of {} bytes exceeds the maximum of {}",
+                    len, MAX_ANALYSIS_JSON_LEN
+                )
+            }
+            ValidationError::InvalidHashHex { len } => {
+                write!(f, "hash of {} characters is not empty or 64 hex characters", len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+impl AnalysisResponse {
+    /// Check the response is well-formed beyond what deserialization enforces.
+    ///
+    /// Severities are already guaranteed valid by the typed field — unknown
+    /// values fail to deserialize. Unknown categories deserialize as
+    /// `ConcernCategory::Unknown` and are accepted.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        try_confidence_to_bps(self.confidence)?;
+
+        if self.concerns.len() > MAX_CONCERNS {
+            return Err(ValidationError::TooManyConcerns { count: self.concerns.len() });
+        }
+
+        for (index, concern) in self.concerns.iter().enumerate() {
+            if concern.description.trim().is_empty() {
+                return Err(ValidationError::EmptyDescription { index });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Basis points for a confidence of 1.0.
+pub const MAX_CONFIDENCE_BPS: u16 = 10_000;
+
+/// Convert a confidence in [0, 1] to basis points, rounding to nearest.
+///
+/// Committed as an integer so the journal never carries a float. Out-of-range
+/// values are clamped and NaN maps to 0; use `try_confidence_to_bps` to
+/// reject them instead.
+pub fn confidence_to_bps(confidence: f64) -> u16 {
+    if confidence.is_nan() {
+        return 0;
+    }
+    // Non-negative after the clamp, so adding 0.5 and truncating rounds.
+    (confidence.clamp(0.0, 1.0) * f64::from(MAX_CONFIDENCE_BPS) + 0.5) as u16
+}
+
+/// Like `confidence_to_bps`, but NaN or a confidence outside [0, 1] is
+/// `ValidationError::ConfidenceOutOfRange`.
+pub fn try_confidence_to_bps(confidence: f64) -> Result<u16, ValidationError> {
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err(ValidationError::ConfidenceOutOfRange(confidence));
+    }
+    Ok(confidence_to_bps(confidence))
+}
+
+/// Convert basis points back to a confidence in [0, 1], clamping values
+/// over `MAX_CONFIDENCE_BPS`.
+///
+/// For display only; `confidence_to_bps` of the result gives back the same
+/// basis points, but anything compared or committed should use the integer.
+pub fn bps_to_confidence(bps: u16) -> f64 {
+    f64::from(bps.min(MAX_CONFIDENCE_BPS)) / f64::from(MAX_CONFIDENCE_BPS)
+}
+
+/// Maximum number of concerns a response may carry.
+///
+/// Responses over the limit are rejected rather than truncated: dropping
+/// concerns could drop the one that decides the verdict. The guest enforces
+/// this so an oversized response can't run away with the cycle budget, and
+/// `validate` enforces it so the host fails before proving starts.
+pub const MAX_CONCERNS: usize = 64;
+
+/// Largest `GuestInput::analysis_json` the guest will evaluate, in bytes.
+///
+/// Parsing is the guest's largest memory and cycle cost, so an unbounded
+/// input could exhaust guest memory before any other check runs. `evaluate`
+/// refuses longer inputs before parsing (the guest then aborts rather than
+/// proving), and the host checks the same limit before proving starts.
+/// Real analyses are a few KB.
+pub const MAX_ANALYSIS_JSON_LEN: usize = 256 * 1024;
+
+/// Default maximum evidence length (mirrors TypeScript MAX_EVIDENCE_LENGTH).
+/// Deployments can override it per input with
+/// `GuestInput::max_evidence_length`.
 pub const MAX_EVIDENCE_LENGTH: usize = 200;
 
+/// Truncate evidence to at most MAX_EVIDENCE_LENGTH bytes.
+///
+/// Cuts on the nearest UTF-8 char boundary at or below the limit, so a
+/// multibyte character straddling byte 200 is dropped rather than split
+/// (byte-index slicing would panic and abort the proof).
+pub fn truncate_evidence(evidence: &str) -> &str {
+    truncate_evidence_to(evidence, MAX_EVIDENCE_LENGTH)
+}
+
+/// Truncate evidence to at most `max_len` bytes, on a char boundary like
+/// `truncate_evidence`.
+pub fn truncate_evidence_to(evidence: &str, max_len: usize) -> &str {
+    if evidence.len() <= max_len {
+        return evidence;
+    }
+
+    let mut end = max_len;
+    while !evidence.is_char_boundary(end) {
+        end -= 1;
+    }
+    &evidence[..end]
+}
+
 /// Input to the zkVM guest program.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuestInput {
+    /// Journal layout the host expects back; the guest refuses any other
+    #[serde(default = "current_schema_version")]
+    pub schema_version: u32,
     /// The raw analysis JSON string from the LLM
     pub analysis_json: String,
     /// SHA-256 hash of the thinking block
@@ -89,21 +535,469 @@ pub struct GuestInput {
     pub values_hash: String,
     /// Model identifier (e.g. "claude-haiku-4-5-20251001")
     pub model: String,
+    /// Verdict rules to apply; defaults to the built-in AIP rules
+    #[serde(default)]
+    pub policy: VerdictPolicy,
+    /// Algorithm for `concerns_hash`; defaults to SHA-256
+    #[serde(default)]
+    pub hash_algo: HashAlgo,
+    /// Evidence truncation length in bytes, applied before deriving and
+    /// hashing; defaults to MAX_EVIDENCE_LENGTH
+    #[serde(default = "default_max_evidence_length")]
+    pub max_evidence_length: u32,
+    /// Proven upstream facts the guest checks with `env::verify`; the host
+    /// adds their receipts to the executor as assumptions
+    #[serde(default)]
+    pub assumptions: Vec<Assumption>,
+}
+
+/// A claim proven by another guest, that a verdict proof composes with
+/// instead of re-proving it (e.g. "this thinking block hashes to X").
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assumption {
+    /// Image ID of the guest that proved the claim
+    pub image_id: [u32; 8],
+    /// That guest's journal
+    pub journal: Vec<u8>,
+}
+
+fn default_max_evidence_length() -> u32 {
+    MAX_EVIDENCE_LENGTH as u32
+}
+
+/// Domain tag that starts `GuestInput::canonical_bytes`. Versioned so the
+/// encoding can change without colliding with earlier hashes.
+const GUEST_INPUT_ENCODING_TAG: &[u8] = b"aip-guest-input/v1";
+
+impl GuestInput {
+    /// Stable byte encoding of the input, for hashing and cache keys.
+    ///
+    /// Unlike the serde forms, this layout is a committed contract and does
+    /// not follow the struct declaration. In order:
+    /// 1. `GUEST_INPUT_ENCODING_TAG`
+    /// 2. `schema_version` as a little-endian `u32`
+    /// 3. `analysis_json`, `thinking_hash`, `card_hash`, `values_hash`,
+    ///    `model`, each as a little-endian `u32` byte length then its UTF-8
+    /// 4. `policy.canonical_bytes()`, length-prefixed the same way
+    /// 5. `hash_algo` as one byte: `0` SHA-256, `1` BLAKE3
+    /// 6. Only if `max_evidence_length` differs from MAX_EVIDENCE_LENGTH, the
+    ///    byte `b'E'` followed by it as a little-endian `u32`; inputs using
+    ///    the default keep their earlier hashes
+    /// 7. Only if there are `assumptions`, the byte `b'A'`, their count as a
+    ///    little-endian `u32`, then each image ID as eight little-endian
+    ///    `u32`s followed by its length-prefixed journal
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        fn put(out: &mut Vec<u8>, bytes: &[u8]) {
+            out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(bytes);
+        }
+
+        let mut out = Vec::from(GUEST_INPUT_ENCODING_TAG);
+        out.extend_from_slice(&self.schema_version.to_le_bytes());
+        for field in [
+            &self.analysis_json,
+            &self.thinking_hash,
+            &self.card_hash,
+            &self.values_hash,
+            &self.model,
+        ] {
+            put(&mut out, field.as_bytes());
+        }
+        put(&mut out, &self.policy.canonical_bytes());
+        out.push(match self.hash_algo {
+            HashAlgo::Sha256 => 0,
+            HashAlgo::Blake3 => 1,
+        });
+        if self.max_evidence_length != default_max_evidence_length() {
+            out.push(b'E');
+            out.extend_from_slice(&self.max_evidence_length.to_le_bytes());
+        }
+        if !self.assumptions.is_empty() {
+            out.push(b'A');
+            out.extend_from_slice(&(self.assumptions.len() as u32).to_le_bytes());
+            for assumption in &self.assumptions {
+                for word in assumption.image_id {
+                    out.extend_from_slice(&word.to_le_bytes());
+                }
+                put(&mut out, &assumption.journal);
+            }
+        }
+        out
+    }
+}
+
+/// Maximum number of inputs in one `GuestInputBatch`.
+///
+/// Each input costs roughly one single-proof's worth of cycles, so this
+/// bounds a batch proof to a handful of segments.
+pub const MAX_BATCH_SIZE: usize = 16;
+
+/// Input to the batch guest program: several analyses proven in one receipt,
+/// amortizing the fixed proving overhead.
+///
+/// Each input is evaluated exactly as the single guest would evaluate it,
+/// including its own policy, hash algorithm and evidence length.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestInputBatch {
+    pub inputs: Vec<GuestInput>,
+}
+
+impl GuestInputBatch {
+    /// Check the batch holds between 1 and MAX_BATCH_SIZE inputs.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.inputs.is_empty() || self.inputs.len() > MAX_BATCH_SIZE {
+            return Err(ValidationError::BatchSize { count: self.inputs.len() });
+        }
+        Ok(())
+    }
+}
+
+/// Version of the `GuestOutput` journal layout. Bump whenever the committed
+/// fields change.
+pub const JOURNAL_SCHEMA_VERSION: u32 = 8;
+
+fn current_schema_version() -> u32 {
+    JOURNAL_SCHEMA_VERSION
+}
+
+/// Read the schema version from a serialized `GuestOutput` journal without
+/// decoding the rest of it.
+///
+/// `schema_version` is the first committed field, and the RISC Zero codec
+/// writes a `u32` as a single little-endian word, so this works for every
+/// layout from version 3 on. Returns `None` if the journal is too short.
+pub fn journal_schema_version(journal: &[u8]) -> Option<u32> {
+    let word = journal.get(..4)?;
+    Some(u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
 }
 
 /// Output committed by the zkVM guest program.
 /// This is what appears in the proof journal.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GuestOutput {
+    /// `JOURNAL_SCHEMA_VERSION` of the guest that produced this journal.
+    /// Must stay the first field so verifiers can check it before decoding.
+    pub schema_version: u32,
     /// The verdict derived by applying rules to concerns
     pub verdict: Verdict,
     /// The action mapped from verdict + severity
     pub action: Action,
-    /// SHA-256 hash of the normalized concerns array
+    /// The model's stated confidence in basis points (0-10000), clamped; see
+    /// `confidence_to_bps` and `bps_to_confidence`
+    pub confidence_bps: u16,
+    /// Hash of the normalized concerns array, computed with `hash_algo`.
+    /// For `Verdict::Malformed`, `hash_raw_analysis` of the unparseable input.
     pub concerns_hash: String,
+    /// Algorithm that produced `concerns_hash`
+    #[serde(default)]
+    pub hash_algo: HashAlgo,
+    /// Evidence truncation length, in bytes, that produced `concerns_hash`
+    #[serde(default = "default_max_evidence_length")]
+    pub max_evidence_length: u32,
+    /// `VerdictPolicy::policy_hash` of the rules the verdict was derived under
+    pub policy_hash: String,
     /// Pass-through input commitment hashes
     pub thinking_hash: String,
     pub card_hash: String,
     pub values_hash: String,
     pub model: String,
+    /// `hash_assumptions` of the upstream claims the guest verified; empty
+    /// when the proof composes with none
+    pub assumptions_hash: String,
+    /// Whether the analysis's own `verdict` string names the derived
+    /// verdict. The derived verdict stays authoritative; `false` records that
+    /// the model misreported it. Always `false` for `Verdict::Malformed`.
+    pub stated_verdict_matches: bool,
+}
+
+/// Journal committed by the batch guest program: one `GuestOutput` per
+/// input, in input order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestOutputBatch {
+    /// `JOURNAL_SCHEMA_VERSION` of the guest that produced this journal; it
+    /// also governs the layout of each output. Must stay the first field.
+    pub schema_version: u32,
+    pub outputs: Vec<GuestOutput>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    /// Display/FromStr must agree with the serde representation.
+    fn assert_string_forms<T>(values: &[T])
+    where
+        T: fmt::Display + FromStr<Err = ParseEnumError> + Serialize + PartialEq + fmt::Debug,
+    {
+        for value in values {
+            let text = value.to_string();
+            assert_eq!(serde_json::to_string(value).unwrap(), alloc::format!("\"{}\"", text));
+            assert_eq!(&text.parse::<T>().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_enum_string_forms_match_serde() {
+        assert_string_forms(&[
+            ConcernCategory::PromptInjection,
+            ConcernCategory::ValueMisalignment,
+            ConcernCategory::AutonomyViolation,
+            ConcernCategory::ReasoningCorruption,
+            ConcernCategory::DeceptiveReasoning,
+            ConcernCategory::UndeclaredIntent,
+        ]);
+        assert_string_forms(&[Severity::Low, Severity::Medium, Severity::High, Severity::Critical]);
+        assert_string_forms(&[
+            Verdict::Clear,
+            Verdict::ReviewNeeded,
+            Verdict::BoundaryViolation,
+            Verdict::Malformed,
+        ]);
+        assert_string_forms(&[
+            Action::Continue,
+            Action::LogAndContinue,
+            Action::PauseForReview,
+            Action::DenyAndEscalate,
+        ]);
+    }
+
+    #[test]
+    fn test_unmodeled_category_deserializes_as_unknown() {
+        let concern: Concern = serde_json::from_str(
+            r#"{"category": "data_exfiltration", "severity": "high", "description": "d"}"#,
+        )
+        .unwrap();
+        assert_eq!(concern.category, ConcernCategory::Unknown("data_exfiltration".into()));
+        assert_eq!(serde_json::to_string(&concern.category).unwrap(), "\"data_exfiltration\"");
+        // FromStr stays strict for every unmodeled name; only serde is lenient.
+        assert!("data_exfiltration".parse::<ConcernCategory>().is_err());
+        assert!("unknown".parse::<ConcernCategory>().is_err());
+    }
+
+    #[test]
+    fn test_enum_parse_rejects_unknown() {
+        let err = "Clear".parse::<Verdict>().unwrap_err();
+        assert_eq!(err.type_name, "Verdict");
+        assert_eq!(err.value, "Clear");
+        assert!("urgent".parse::<Severity>().is_err());
+    }
+
+    #[test]
+    fn test_severity_u8_roundtrip() {
+        let all = [Severity::Low, Severity::Medium, Severity::High, Severity::Critical];
+        for (rank, severity) in all.iter().enumerate() {
+            assert_eq!(severity.as_u8(), rank as u8);
+            assert_eq!(Severity::from_u8(rank as u8).as_ref(), Some(severity));
+        }
+        for pair in all.windows(2) {
+            assert!(pair[0] < pair[1] && pair[0].as_u8() < pair[1].as_u8());
+        }
+        assert_eq!(Severity::from_u8(4), None);
+    }
+
+    #[test]
+    fn test_analysis_response_accepts_camel_case_keys() {
+        let snake = r#"{"verdict": "review_needed", "concerns": [{"category": "autonomy_violation",
+            "severity": "medium", "description": "d", "evidence": "e"}], "confidence": 0.8,
+            "reasoning_summary": "s"}"#;
+        let camel = snake.replace("reasoning_summary", "reasoningSummary");
+
+        for json in [snake, camel.as_str()] {
+            let response: AnalysisResponse = serde_json::from_str(json).unwrap();
+            assert_eq!(response.reasoning_summary, "s");
+            assert_eq!(response.concerns[0].category, ConcernCategory::AutonomyViolation);
+            assert_eq!(response.concerns[0].evidence, "e");
+        }
+        let reencoded = serde_json::to_string(&serde_json::from_str::<AnalysisResponse>(&camel).unwrap()).unwrap();
+        assert!(reencoded.contains("\"reasoning_summary\""), "{}", reencoded);
+    }
+
+    fn make_response(confidence: f64, description: &str) -> AnalysisResponse {
+        AnalysisResponse {
+            verdict: "clear".to_string(),
+            concerns: alloc::vec![Concern {
+                category: ConcernCategory::ValueMisalignment,
+                severity: Severity::Low,
+                description: description.to_string(),
+                evidence: String::new(),
+            }],
+            confidence,
+            reasoning_summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_well_formed() {
+        assert_eq!(make_response(0.0, "ok").validate(), Ok(()));
+        assert_eq!(make_response(1.0, "ok").validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_confidence_range() {
+        assert_eq!(
+            make_response(1.5, "ok").validate(),
+            Err(ValidationError::ConfidenceOutOfRange(1.5))
+        );
+        assert!(make_response(-0.1, "ok").validate().is_err());
+        assert!(make_response(f64::NAN, "ok").validate().is_err());
+    }
+
+    #[test]
+    fn test_guest_input_canonical_bytes_golden() {
+        // Changing these bytes changes every input hash and cache key; only
+        // do so together with a new GUEST_INPUT_ENCODING_TAG.
+        let input = GuestInput {
+            schema_version: 3,
+            analysis_json: "{}".to_string(),
+            thinking_hash: "t".to_string(),
+            card_hash: "c".to_string(),
+            values_hash: "v".to_string(),
+            model: "m".to_string(),
+            policy: VerdictPolicy::default(),
+            hash_algo: HashAlgo::Sha256,
+            max_evidence_length: MAX_EVIDENCE_LENGTH as u32,
+            assumptions: Vec::new(),
+        };
+        let expected = concat!(
+            "6169702d67756573742d696e7075742f7631", // "aip-guest-input/v1"
+            "03000000",                             // schema_version
+            "020000007b7d",                         // analysis_json "{}"
+            "0100000074", "0100000063", "0100000076", "010000006d",
+            "56000000",                             // policy length
+            "6169702d766572646963742d706f6c6963792f7631030103000000",
+            "70726f6d70745f696e6a656374696f6e0002",
+            "6465636570746976655f726561736f6e696e670002",
+            "76616c75655f6d6973616c69676e6d656e740002",
+            "00",                                   // hash_algo
+        );
+        assert_eq!(hex::encode(input.canonical_bytes()), expected);
+
+        let blake3 = GuestInput { hash_algo: HashAlgo::Blake3, ..input.clone() };
+        assert_eq!(blake3.canonical_bytes().last(), Some(&1));
+
+        // A non-default evidence length is appended after hash_algo.
+        let short_evidence = GuestInput { max_evidence_length: 80, ..input.clone() };
+        assert_eq!(
+            hex::encode(short_evidence.canonical_bytes()),
+            alloc::format!("{}{}", expected, "4550000000")
+        );
+
+        // Assumptions, if any, come last.
+        let assumption = Assumption { image_id: [1, 0, 0, 0, 0, 0, 0, 2], journal: alloc::vec![0xab] };
+        let composed = GuestInput { assumptions: alloc::vec![assumption], ..input.clone() };
+        assert_eq!(
+            hex::encode(composed.canonical_bytes()),
+            alloc::format!(
+                "{}41{}{}{}{}",
+                expected,
+                "01000000",
+                "01000000".to_string() + &"00000000".repeat(6),
+                "02000000",
+                "01000000ab"
+            )
+        );
+
+        // Length prefixes keep field boundaries unambiguous.
+        let shifted = GuestInput {
+            thinking_hash: "tc".to_string(),
+            card_hash: String::new(),
+            ..input.clone()
+        };
+        assert_ne!(shifted.canonical_bytes(), input.canonical_bytes());
+    }
+
+    #[test]
+    fn test_confidence_to_bps() {
+        assert_eq!(confidence_to_bps(0.0), 0);
+        assert_eq!(confidence_to_bps(1.0), 10_000);
+        assert_eq!(confidence_to_bps(0.95), 9_500);
+        assert_eq!(confidence_to_bps(0.12345), 1_235);
+        assert_eq!(confidence_to_bps(1.5), 10_000);
+        assert_eq!(confidence_to_bps(-0.1), 0);
+        assert_eq!(confidence_to_bps(f64::NAN), 0);
+    }
+
+    #[test]
+    fn test_confidence_bps_roundtrip() {
+        for bps in 0..=MAX_CONFIDENCE_BPS {
+            assert_eq!(confidence_to_bps(bps_to_confidence(bps)), bps);
+            assert_eq!(try_confidence_to_bps(bps_to_confidence(bps)), Ok(bps));
+        }
+        assert_eq!(bps_to_confidence(9_500), 0.95);
+        assert_eq!(bps_to_confidence(u16::MAX), 1.0);
+    }
+
+    #[test]
+    fn test_try_confidence_to_bps_rejects_out_of_range() {
+        assert_eq!(try_confidence_to_bps(1.0), Ok(MAX_CONFIDENCE_BPS));
+        assert_eq!(try_confidence_to_bps(1.5), Err(ValidationError::ConfidenceOutOfRange(1.5)));
+        assert_eq!(try_confidence_to_bps(-0.1), Err(ValidationError::ConfidenceOutOfRange(-0.1)));
+        assert!(matches!(
+            try_confidence_to_bps(f64::NAN),
+            Err(ValidationError::ConfidenceOutOfRange(c)) if c.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_validate_concern_count_boundary() {
+        let mut response = make_response(0.5, "ok");
+        let concern = response.concerns[0].clone();
+        response.concerns = alloc::vec![concern; MAX_CONCERNS];
+        assert_eq!(response.validate(), Ok(()));
+
+        response.concerns.push(response.concerns[0].clone());
+        assert_eq!(
+            response.validate(),
+            Err(ValidationError::TooManyConcerns { count: MAX_CONCERNS + 1 })
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_description() {
+        assert_eq!(
+            make_response(0.5, "  ").validate(),
+            Err(ValidationError::EmptyDescription { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_truncate_evidence_short_unchanged() {
+        assert_eq!(truncate_evidence("short"), "short");
+        let exact = "x".repeat(MAX_EVIDENCE_LENGTH);
+        assert_eq!(truncate_evidence(&exact), exact);
+    }
+
+    #[test]
+    fn test_truncate_evidence_splits_emoji() {
+        // 198 ASCII bytes + a 4-byte emoji puts byte 200 inside the emoji.
+        let evidence = "a".repeat(198) + "\u{1F600}" + "tail";
+        assert!(!evidence.is_char_boundary(MAX_EVIDENCE_LENGTH));
+
+        let truncated = truncate_evidence(&evidence);
+        assert_eq!(truncated, "a".repeat(198));
+        assert!(truncated.len() <= MAX_EVIDENCE_LENGTH);
+    }
+
+    #[test]
+    fn test_truncate_evidence_to_custom_length() {
+        let evidence = "ab\u{1F600}cd"; // emoji occupies bytes 2..6
+        assert_eq!(truncate_evidence_to(evidence, 0), "");
+        assert_eq!(truncate_evidence_to(evidence, 4), "ab");
+        assert_eq!(truncate_evidence_to(evidence, 6), "ab\u{1F600}");
+        assert_eq!(truncate_evidence_to(evidence, 1_000), evidence);
+
+        let long = "x".repeat(500);
+        assert_eq!(truncate_evidence_to(&long, 300).len(), 300);
+        assert_eq!(truncate_evidence_to(&long, MAX_EVIDENCE_LENGTH), truncate_evidence(&long));
+    }
+
+    #[test]
+    fn test_truncate_evidence_multibyte_fits() {
+        let evidence = "é".repeat(150); // 300 bytes, 2 bytes per char
+        let truncated = truncate_evidence(&evidence);
+        assert_eq!(truncated.len(), MAX_EVIDENCE_LENGTH);
+        assert_eq!(truncated, "é".repeat(100).to_string());
+    }
 }
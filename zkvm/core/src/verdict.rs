@@ -7,8 +7,165 @@
 //! The zkVM guest program re-derives the verdict from concerns using these
 //! rules. It does NOT trust the LLM's stated verdict.
 
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
 use crate::types::{Action, Concern, ConcernCategory, Severity, Verdict};
 
+/// Severity at which concerns of one category become a boundary violation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub category: ConcernCategory,
+    pub boundary_severity: Severity,
+}
+
+/// Thresholds used to derive a verdict from concerns.
+///
+/// Serializable so it can be passed to the guest in `GuestInput`. The
+/// `Default` policy reproduces the TypeScript rules exactly (see
+/// `derive_verdict`): critical is a boundary violation in every category,
+/// and high is one only for prompt_injection, deceptive_reasoning and
+/// value_misalignment. High autonomy_violation, reasoning_corruption and
+/// undeclared_intent need review; opt them into escalation with
+/// `with_category_rule`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerdictPolicy {
+    /// Any concern at or above this severity is a boundary violation.
+    pub boundary_severity: Severity,
+    /// Per-category severities at or above which a concern is a boundary
+    /// violation, checked in addition to `boundary_severity`.
+    pub category_rules: Vec<CategoryRule>,
+    /// Any concern at or above this severity (not meeting boundary criteria)
+    /// needs review.
+    pub review_severity: Severity,
+    /// This many concerns at medium or above need review, whatever their
+    /// category. `None` (or 0) disables the count check.
+    #[serde(default)]
+    pub medium_count_for_review: Option<u32>,
+    /// This many concerns at medium or above are a boundary violation,
+    /// whatever their category. `None` (or 0) disables the count check.
+    #[serde(default)]
+    pub medium_count_for_boundary: Option<u32>,
+}
+
+impl Default for VerdictPolicy {
+    fn default() -> Self {
+        let high = |category| CategoryRule { category, boundary_severity: Severity::High };
+        VerdictPolicy {
+            boundary_severity: Severity::Critical,
+            category_rules: vec![
+                high(ConcernCategory::PromptInjection),
+                high(ConcernCategory::DeceptiveReasoning),
+                high(ConcernCategory::ValueMisalignment),
+            ],
+            review_severity: Severity::Medium,
+            medium_count_for_review: None,
+            medium_count_for_boundary: None,
+        }
+    }
+}
+
+/// Domain tag that starts `VerdictPolicy::canonical_bytes`. Versioned so the
+/// encoding can change without colliding with earlier hashes.
+const POLICY_ENCODING_TAG: &[u8] = b"aip-verdict-policy/v1";
+
+impl VerdictPolicy {
+    /// Stable byte encoding of the policy, hashed by `policy_hash`.
+    ///
+    /// `POLICY_ENCODING_TAG`, then `boundary_severity` and `review_severity`
+    /// as one byte each (`Severity::as_u8`), then the rule count as a
+    /// little-endian `u32`, then for each rule in order its category name,
+    /// a `0` byte and its severity byte. Then, only if a medium-count
+    /// threshold is set, the byte `b'M'` followed by `medium_count_for_review`
+    /// and `medium_count_for_boundary` as little-endian `u32`s (0 if unset);
+    /// policies without count thresholds keep their earlier hashes.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::from(POLICY_ENCODING_TAG);
+        out.push(self.boundary_severity.as_u8());
+        out.push(self.review_severity.as_u8());
+        out.extend_from_slice(&(self.category_rules.len() as u32).to_le_bytes());
+        for rule in &self.category_rules {
+            out.extend_from_slice(rule.category.as_str().as_bytes());
+            out.push(0);
+            out.push(rule.boundary_severity.as_u8());
+        }
+        let (review, boundary) = (self.review_count(), self.boundary_count());
+        if review.is_some() || boundary.is_some() {
+            out.push(b'M');
+            out.extend_from_slice(&review.unwrap_or(0).to_le_bytes());
+            out.extend_from_slice(&boundary.unwrap_or(0).to_le_bytes());
+        }
+        out
+    }
+
+    /// Make `category` a boundary violation at `boundary_severity` and above,
+    /// replacing any existing rule for it in place (so other rules keep their
+    /// order, and with it the `policy_hash`) or appending a new one.
+    pub fn with_category_rule(mut self, category: ConcernCategory, boundary_severity: Severity) -> Self {
+        match self.category_rules.iter_mut().find(|rule| rule.category == category) {
+            Some(rule) => rule.boundary_severity = boundary_severity,
+            None => self.category_rules.push(CategoryRule { category, boundary_severity }),
+        }
+        self
+    }
+
+    /// `medium_count_for_review`, with 0 treated as unset.
+    fn review_count(&self) -> Option<u32> {
+        self.medium_count_for_review.filter(|&n| n > 0)
+    }
+
+    /// `medium_count_for_boundary`, with 0 treated as unset.
+    fn boundary_count(&self) -> Option<u32> {
+        self.medium_count_for_boundary.filter(|&n| n > 0)
+    }
+
+    /// Hex SHA-256 of `canonical_bytes`. The guest commits this so verifiers
+    /// can confirm which rules produced a verdict.
+    pub fn policy_hash(&self) -> String {
+        hex::encode(Sha256::digest(self.canonical_bytes()))
+    }
+
+    /// Whether a single concern meets boundary-violation criteria.
+    ///
+    /// Category rules never match `ConcernCategory::Unknown`, even a rule
+    /// naming it: an unrecognized category says nothing about how serious
+    /// the concern is, so only `boundary_severity` applies to it.
+    fn is_boundary(&self, concern: &Concern) -> bool {
+        concern.severity >= self.boundary_severity
+            || (!matches!(concern.category, ConcernCategory::Unknown(_))
+                && self.category_rules.iter().any(|rule| {
+                    rule.category == concern.category && concern.severity >= rule.boundary_severity
+                }))
+    }
+}
+
+/// The concern that decided a verdict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecidingConcern {
+    /// Index of the concern in the input slice
+    pub index: usize,
+    pub category: ConcernCategory,
+    pub severity: Severity,
+}
+
+/// Why `derive_verdict_explained` reached its verdict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerdictReason {
+    /// BoundaryViolation: the first concern meeting boundary criteria.
+    Boundary(DecidingConcern),
+    /// ReviewNeeded: the first concern at or above the review severity.
+    Review(DecidingConcern),
+    /// BoundaryViolation or ReviewNeeded: this many concerns at medium or
+    /// above met the policy's medium-count threshold.
+    MediumCount(u32),
+    /// Clear: no concern at or above the review severity (medium by default).
+    NoMediumPlusConcerns,
+}
+
 /// Derive the integrity verdict from a list of concerns.
 ///
 /// Rules (from prompt.ts lines 137-140):
@@ -17,41 +174,61 @@ use crate::types::{Action, Concern, ConcernCategory, Severity, Verdict};
 ///   BOUNDARY-type value match with high/critical (value_misalignment with high/critical)
 /// - REVIEW_NEEDED: any medium+ severity concern not meeting boundary criteria
 /// - CLEAR: no medium+ severity concerns
+///
+/// A `ConcernCategory::Unknown` concern is a boundary violation only at
+/// critical severity; at medium or high it needs review.
 pub fn derive_verdict(concerns: &[Concern]) -> Verdict {
-    let mut has_medium_plus = false;
+    derive_verdict_with_policy(concerns, &VerdictPolicy::default())
+}
 
-    for concern in concerns {
-        // Any critical → boundary violation
-        if concern.severity == Severity::Critical {
-            return Verdict::BoundaryViolation;
-        }
+/// Derive the integrity verdict from a list of concerns under `policy`.
+pub fn derive_verdict_with_policy(concerns: &[Concern], policy: &VerdictPolicy) -> Verdict {
+    derive_verdict_explained_with_policy(concerns, policy).0
+}
 
-        // High severity in boundary categories
-        if concern.severity == Severity::High {
-            match concern.category {
-                ConcernCategory::PromptInjection
-                | ConcernCategory::DeceptiveReasoning => {
-                    return Verdict::BoundaryViolation;
-                }
-                // Value misalignment with high severity is also boundary
-                ConcernCategory::ValueMisalignment => {
-                    return Verdict::BoundaryViolation;
-                }
-                _ => {
-                    has_medium_plus = true;
-                }
-            }
+/// Derive the verdict under the default rules, along with the reason for it.
+pub fn derive_verdict_explained(concerns: &[Concern]) -> (Verdict, VerdictReason) {
+    derive_verdict_explained_with_policy(concerns, &VerdictPolicy::default())
+}
+
+/// Derive the verdict under `policy`, along with the reason for it.
+pub fn derive_verdict_explained_with_policy(
+    concerns: &[Concern],
+    policy: &VerdictPolicy,
+) -> (Verdict, VerdictReason) {
+    let mut first_review: Option<DecidingConcern> = None;
+    let mut medium_plus: u32 = 0;
+
+    for (index, concern) in concerns.iter().enumerate() {
+        let deciding = || DecidingConcern {
+            index,
+            category: concern.category.clone(),
+            severity: concern.severity.clone(),
+        };
+
+        if policy.is_boundary(concern) {
+            return (Verdict::BoundaryViolation, VerdictReason::Boundary(deciding()));
         }
 
-        if concern.severity == Severity::Medium {
-            has_medium_plus = true;
+        if first_review.is_none() && concern.severity >= policy.review_severity {
+            first_review = Some(deciding());
         }
+        if concern.severity >= Severity::Medium {
+            medium_plus += 1;
+        }
+    }
+
+    let reached = |threshold: Option<u32>| threshold.is_some_and(|n| medium_plus >= n);
+    if reached(policy.boundary_count()) {
+        return (Verdict::BoundaryViolation, VerdictReason::MediumCount(medium_plus));
     }
 
-    if has_medium_plus {
-        Verdict::ReviewNeeded
-    } else {
-        Verdict::Clear
+    match first_review {
+        Some(deciding) => (Verdict::ReviewNeeded, VerdictReason::Review(deciding)),
+        None if reached(policy.review_count()) => {
+            (Verdict::ReviewNeeded, VerdictReason::MediumCount(medium_plus))
+        }
+        None => (Verdict::Clear, VerdictReason::NoMediumPlusConcerns),
     }
 }
 
@@ -62,8 +239,12 @@ pub fn derive_verdict(concerns: &[Concern]) -> Verdict {
 /// - review_needed → log_and_continue
 /// - boundary_violation with any critical → deny_and_escalate
 /// - boundary_violation without critical → pause_for_review
+///
+/// `Verdict::Malformed` has no TypeScript counterpart; it maps to
+/// pause_for_review so unreadable analyses get a human look.
 pub fn map_verdict_to_action(verdict: &Verdict, concerns: &[Concern]) -> Action {
     match verdict {
+        Verdict::Malformed => Action::PauseForReview,
         Verdict::Clear => Action::Continue,
         Verdict::ReviewNeeded => Action::LogAndContinue,
         Verdict::BoundaryViolation => {
@@ -81,8 +262,6 @@ pub fn map_verdict_to_action(verdict: &Verdict, concerns: &[Concern]) -> Action
 mod tests {
     use super::*;
     use alloc::string::String;
-    use alloc::vec;
-    use alloc::vec::Vec;
 
     fn make_concern(category: ConcernCategory, severity: Severity) -> Concern {
         Concern {
@@ -93,6 +272,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_default_policy_hash_pinned() {
+        // Changing this means every verifier pinning the built-in rules must
+        // be updated; only do so together with a rules change.
+        assert_eq!(VerdictPolicy::default().policy_hash(), "54a1ccfea7467d46fe1bed58d5a21d1ea73ec83c603b80b941ed4144c2d72cee");
+    }
+
+    #[test]
+    fn test_policy_hash_covers_every_field() {
+        let base = VerdictPolicy::default();
+        let mut stricter = base.clone();
+        stricter.review_severity = Severity::Low;
+        let mut reordered = base.clone();
+        reordered.category_rules.reverse();
+        let mut fewer = base.clone();
+        fewer.category_rules.pop();
+
+        for other in [stricter, reordered, fewer] {
+            assert_ne!(other.policy_hash(), base.policy_hash());
+        }
+    }
+
+    #[test]
+    fn test_unknown_category_escalates_on_severity_only() {
+        let exfiltration = || ConcernCategory::Unknown("data_exfiltration".into());
+        let unknown = |severity| vec![make_concern(exfiltration(), severity)];
+        assert_eq!(derive_verdict(&unknown(Severity::Low)), Verdict::Clear);
+        assert_eq!(derive_verdict(&unknown(Severity::Medium)), Verdict::ReviewNeeded);
+        assert_eq!(derive_verdict(&unknown(Severity::High)), Verdict::ReviewNeeded);
+        assert_eq!(derive_verdict(&unknown(Severity::Critical)), Verdict::BoundaryViolation);
+
+        // A rule naming the same unmodeled category is ignored.
+        let mut policy = VerdictPolicy::default();
+        policy.category_rules.push(CategoryRule {
+            category: exfiltration(),
+            boundary_severity: Severity::Low,
+        });
+        assert_eq!(derive_verdict_with_policy(&unknown(Severity::High), &policy), Verdict::ReviewNeeded);
+    }
+
     #[test]
     fn test_clear_no_concerns() {
         let concerns: Vec<Concern> = vec![];
@@ -182,6 +401,182 @@ mod tests {
         assert_eq!(derive_verdict(&concerns), Verdict::ReviewNeeded);
     }
 
+    #[test]
+    fn test_default_policy_per_category() {
+        let escalating_at_high = [
+            ConcernCategory::PromptInjection,
+            ConcernCategory::DeceptiveReasoning,
+            ConcernCategory::ValueMisalignment,
+        ];
+        let review_at_high = [
+            ConcernCategory::AutonomyViolation,
+            ConcernCategory::ReasoningCorruption,
+            ConcernCategory::UndeclaredIntent,
+        ];
+        for category in escalating_at_high.iter().chain(&review_at_high) {
+            let at = |severity| derive_verdict(&[make_concern(category.clone(), severity)]);
+            let expected_high = if escalating_at_high.contains(category) {
+                Verdict::BoundaryViolation
+            } else {
+                Verdict::ReviewNeeded
+            };
+            assert_eq!(at(Severity::High), expected_high, "{} at high", category.as_str());
+            assert_eq!(at(Severity::Critical), Verdict::BoundaryViolation, "{} at critical", category.as_str());
+        }
+    }
+
+    #[test]
+    fn test_policy_opts_categories_into_high_escalation() {
+        for category in [
+            ConcernCategory::AutonomyViolation,
+            ConcernCategory::ReasoningCorruption,
+            ConcernCategory::UndeclaredIntent,
+        ] {
+            let policy = VerdictPolicy::default().with_category_rule(category.clone(), Severity::High);
+            assert_ne!(policy.policy_hash(), VerdictPolicy::default().policy_hash());
+            let at = |severity| derive_verdict_with_policy(&[make_concern(category.clone(), severity)], &policy);
+            assert_eq!(at(Severity::Medium), Verdict::ReviewNeeded, "{} at medium", category.as_str());
+            assert_eq!(at(Severity::High), Verdict::BoundaryViolation, "{} at high", category.as_str());
+            assert_eq!(at(Severity::Critical), Verdict::BoundaryViolation, "{} at critical", category.as_str());
+        }
+    }
+
+    #[test]
+    fn test_with_category_rule_replaces_in_place() {
+        let unchanged = VerdictPolicy::default().with_category_rule(ConcernCategory::PromptInjection, Severity::High);
+        assert_eq!(unchanged, VerdictPolicy::default());
+
+        let relaxed = VerdictPolicy::default().with_category_rule(ConcernCategory::PromptInjection, Severity::Critical);
+        assert_eq!(relaxed.category_rules.len(), VerdictPolicy::default().category_rules.len());
+        assert_eq!(relaxed.category_rules[0].boundary_severity, Severity::Critical);
+        let concerns = [make_concern(ConcernCategory::PromptInjection, Severity::High)];
+        assert_eq!(derive_verdict_with_policy(&concerns, &relaxed), Verdict::ReviewNeeded);
+    }
+
+    #[test]
+    fn test_policy_high_value_misalignment_as_review() {
+        let mut policy = VerdictPolicy::default();
+        policy
+            .category_rules
+            .retain(|rule| rule.category != ConcernCategory::ValueMisalignment);
+
+        let concerns = vec![
+            make_concern(ConcernCategory::ValueMisalignment, Severity::High),
+        ];
+        assert_eq!(derive_verdict_with_policy(&concerns, &policy), Verdict::ReviewNeeded);
+        assert_eq!(derive_verdict(&concerns), Verdict::BoundaryViolation);
+    }
+
+    #[test]
+    fn test_policy_stricter_review_threshold() {
+        let policy = VerdictPolicy {
+            review_severity: Severity::Low,
+            ..VerdictPolicy::default()
+        };
+        let concerns = vec![
+            make_concern(ConcernCategory::ReasoningCorruption, Severity::Low),
+        ];
+        assert_eq!(derive_verdict_with_policy(&concerns, &policy), Verdict::ReviewNeeded);
+        assert_eq!(derive_verdict(&concerns), Verdict::Clear);
+    }
+
+    #[test]
+    fn test_policy_medium_count_thresholds() {
+        // Single mediums don't need review here; only the counts escalate.
+        let policy = VerdictPolicy {
+            review_severity: Severity::High,
+            medium_count_for_review: Some(3),
+            medium_count_for_boundary: Some(5),
+            ..VerdictPolicy::default()
+        };
+        let mediums = |n| vec![make_concern(ConcernCategory::AutonomyViolation, Severity::Medium); n];
+
+        let derive = |n| derive_verdict_explained_with_policy(&mediums(n), &policy);
+        assert_eq!(derive(2), (Verdict::Clear, VerdictReason::NoMediumPlusConcerns));
+        assert_eq!(derive(3), (Verdict::ReviewNeeded, VerdictReason::MediumCount(3)));
+        assert_eq!(derive(4), (Verdict::ReviewNeeded, VerdictReason::MediumCount(4)));
+        assert_eq!(derive(5), (Verdict::BoundaryViolation, VerdictReason::MediumCount(5)));
+        assert_eq!(
+            map_verdict_to_action(&Verdict::BoundaryViolation, &mediums(5)),
+            Action::PauseForReview
+        );
+
+        // The defaults leave the counts off: five mediums are only a review.
+        assert_eq!(derive_verdict(&mediums(5)), Verdict::ReviewNeeded);
+    }
+
+    #[test]
+    fn test_policy_medium_counts_change_hash() {
+        let with_counts = VerdictPolicy {
+            medium_count_for_boundary: Some(5),
+            ..VerdictPolicy::default()
+        };
+        assert_ne!(with_counts.policy_hash(), VerdictPolicy::default().policy_hash());
+
+        // 0 means unset, so it hashes like the default.
+        let zero = VerdictPolicy {
+            medium_count_for_review: Some(0),
+            ..VerdictPolicy::default()
+        };
+        assert_eq!(zero.policy_hash(), VerdictPolicy::default().policy_hash());
+    }
+
+    #[test]
+    fn test_policy_serde_roundtrip() {
+        let policy = VerdictPolicy::default();
+        let json = serde_json::to_string(&policy).unwrap();
+        let restored: VerdictPolicy = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, policy);
+    }
+
+    #[test]
+    fn test_explained_clear() {
+        let concerns = vec![
+            make_concern(ConcernCategory::ValueMisalignment, Severity::Low),
+        ];
+        assert_eq!(
+            derive_verdict_explained(&concerns),
+            (Verdict::Clear, VerdictReason::NoMediumPlusConcerns)
+        );
+    }
+
+    #[test]
+    fn test_explained_review_first_medium_plus() {
+        let concerns = vec![
+            make_concern(ConcernCategory::ValueMisalignment, Severity::Low),
+            make_concern(ConcernCategory::AutonomyViolation, Severity::High),
+            make_concern(ConcernCategory::UndeclaredIntent, Severity::Medium),
+        ];
+        let (verdict, reason) = derive_verdict_explained(&concerns);
+        assert_eq!(verdict, Verdict::ReviewNeeded);
+        assert_eq!(
+            reason,
+            VerdictReason::Review(DecidingConcern {
+                index: 1,
+                category: ConcernCategory::AutonomyViolation,
+                severity: Severity::High,
+            })
+        );
+    }
+
+    #[test]
+    fn test_explained_boundary_deciding_concern() {
+        let concerns = vec![
+            make_concern(ConcernCategory::ValueMisalignment, Severity::Medium),
+            make_concern(ConcernCategory::PromptInjection, Severity::Critical),
+        ];
+        let (verdict, reason) = derive_verdict_explained(&concerns);
+        assert_eq!(verdict, Verdict::BoundaryViolation);
+        assert_eq!(
+            reason,
+            VerdictReason::Boundary(DecidingConcern {
+                index: 1,
+                category: ConcernCategory::PromptInjection,
+                severity: Severity::Critical,
+            })
+        );
+    }
+
     #[test]
     fn test_mixed_concerns_boundary_wins() {
         let concerns = vec![
@@ -194,4 +589,121 @@ mod tests {
             Action::DenyAndEscalate
         );
     }
+
+    /// Property tests against an oracle that encodes the default rules
+    /// directly from the spec, independently of `VerdictPolicy`.
+    #[cfg(feature = "std")]
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn category() -> impl Strategy<Value = ConcernCategory> {
+            prop::sample::select(vec![
+                ConcernCategory::PromptInjection,
+                ConcernCategory::ValueMisalignment,
+                ConcernCategory::AutonomyViolation,
+                ConcernCategory::ReasoningCorruption,
+                ConcernCategory::DeceptiveReasoning,
+                ConcernCategory::UndeclaredIntent,
+            ])
+        }
+
+        fn severity() -> impl Strategy<Value = Severity> {
+            prop::sample::select(vec![Severity::Low, Severity::Medium, Severity::High, Severity::Critical])
+        }
+
+        fn concerns() -> impl Strategy<Value = Vec<Concern>> {
+            prop::collection::vec(
+                (category(), severity()).prop_map(|(category, severity)| make_concern(category, severity)),
+                0..12,
+            )
+        }
+
+        /// prompt.ts: critical anywhere, or high in one of the boundary
+        /// categories, is a boundary violation; any other medium+ needs review.
+        fn oracle(concerns: &[Concern]) -> Verdict {
+            let boundary = concerns.iter().any(|c| match c.severity {
+                Severity::Critical => true,
+                Severity::High => matches!(
+                    c.category,
+                    ConcernCategory::PromptInjection
+                        | ConcernCategory::DeceptiveReasoning
+                        | ConcernCategory::ValueMisalignment
+                ),
+                _ => false,
+            });
+            let medium_plus = concerns.iter().any(|c| c.severity >= Severity::Medium);
+            if boundary {
+                Verdict::BoundaryViolation
+            } else if medium_plus {
+                Verdict::ReviewNeeded
+            } else {
+                Verdict::Clear
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn matches_oracle(concerns in concerns()) {
+                prop_assert_eq!(derive_verdict(&concerns), oracle(&concerns));
+            }
+
+            #[test]
+            fn critical_forces_boundary(mut concerns in concerns(), category in category(), at in any::<prop::sample::Index>()) {
+                let index = at.index(concerns.len() + 1);
+                concerns.insert(index, make_concern(category, Severity::Critical));
+                prop_assert_eq!(derive_verdict(&concerns), Verdict::BoundaryViolation);
+                prop_assert_eq!(map_verdict_to_action(&Verdict::BoundaryViolation, &concerns), Action::DenyAndEscalate);
+            }
+
+            #[test]
+            fn clear_iff_no_medium_plus(concerns in concerns()) {
+                let medium_plus = concerns.iter().any(|c| c.severity >= Severity::Medium);
+                prop_assert_eq!(derive_verdict(&concerns) == Verdict::Clear, !medium_plus);
+            }
+
+            #[test]
+            fn order_does_not_change_verdict(concerns in concerns()) {
+                let mut reversed = concerns.clone();
+                reversed.reverse();
+                prop_assert_eq!(derive_verdict(&concerns), derive_verdict(&reversed));
+            }
+
+            #[test]
+            fn action_mapping_is_total_and_consistent(concerns in concerns()) {
+                let has_critical = concerns.iter().any(|c| c.severity == Severity::Critical);
+                for verdict in [Verdict::Clear, Verdict::ReviewNeeded, Verdict::BoundaryViolation, Verdict::Malformed] {
+                    let expected = match verdict {
+                        Verdict::Malformed => Action::PauseForReview,
+                        Verdict::Clear => Action::Continue,
+                        Verdict::ReviewNeeded => Action::LogAndContinue,
+                        Verdict::BoundaryViolation if has_critical => Action::DenyAndEscalate,
+                        Verdict::BoundaryViolation => Action::PauseForReview,
+                    };
+                    prop_assert_eq!(map_verdict_to_action(&verdict, &concerns), expected);
+                }
+            }
+
+            #[test]
+            fn reason_points_at_deciding_concern(concerns in concerns()) {
+                let (verdict, reason) = derive_verdict_explained(&concerns);
+                match reason {
+                    VerdictReason::Boundary(d) | VerdictReason::Review(d) => {
+                        let concern = &concerns[d.index];
+                        prop_assert_eq!(&d.category, &concern.category);
+                        prop_assert_eq!(&d.severity, &concern.severity);
+                        prop_assert!(verdict != Verdict::Clear);
+                        // Nothing before the deciding concern could have decided it.
+                        prop_assert_eq!(oracle(&concerns[..d.index]) == Verdict::BoundaryViolation, false);
+                        if verdict == Verdict::ReviewNeeded {
+                            prop_assert_eq!(oracle(&concerns[..d.index]), Verdict::Clear);
+                        }
+                    }
+                    VerdictReason::NoMediumPlusConcerns => prop_assert_eq!(verdict, Verdict::Clear),
+                    // The default policy has no count thresholds.
+                    VerdictReason::MediumCount(_) => prop_assert!(false, "count reason under default policy"),
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,53 @@
+/*
+ * C interface for verifying AIP verdict receipts.
+ *
+ * Link against the aip_zkvm_ffi cdylib or staticlib. Every function returns
+ * an int32_t status code: AIP_OK (0) or a non-negative length on success, a
+ * negative AIP_ERR_* code on failure. Rust panics never unwind into the
+ * caller; they are reported as AIP_ERR_INTERNAL.
+ */
+#ifndef AIP_ZKVM_H
+#define AIP_ZKVM_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AIP_OK                     0
+#define AIP_ERR_NULL_POINTER      -1  /* a required pointer was null */
+#define AIP_ERR_INVALID_IMAGE_ID  -2  /* image ID is not 64 hex characters */
+#define AIP_ERR_DESERIALIZE       -3  /* receipt bytes do not deserialize */
+#define AIP_ERR_VERIFY            -4  /* proof rejected, or dev-mode receipt */
+#define AIP_ERR_SCHEMA_VERSION    -5  /* journal schema version not supported */
+#define AIP_ERR_DECODE_JOURNAL    -6  /* journal does not decode */
+#define AIP_ERR_BUFFER_TOO_SMALL  -7  /* out_len < JSON length + 1 */
+#define AIP_ERR_INTERNAL         -99  /* any other error, including a panic */
+
+/*
+ * Verify a bincode-serialized receipt.
+ *
+ * image_id_hex is a NUL-terminated 64-char hex image ID, or NULL for the
+ * guest embedded in the library. Returns AIP_OK if the proof verifies and
+ * its journal decodes.
+ */
+int32_t aip_verify_receipt(const uint8_t *receipt, size_t receipt_len,
+                           const char *image_id_hex);
+
+/*
+ * Decode a receipt's journal as a NUL-terminated JSON object written to out.
+ *
+ * Does NOT verify the proof; call aip_verify_receipt first. Returns the JSON
+ * length excluding the NUL. With out == NULL and out_len == 0, returns the
+ * length without writing so the caller can size the buffer.
+ */
+int32_t aip_decode_verdict(const uint8_t *receipt, size_t receipt_len,
+                           char *out, size_t out_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AIP_ZKVM_H */
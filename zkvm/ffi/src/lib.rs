@@ -0,0 +1,194 @@
+//! C ABI for verifying AIP verdict receipts from native code.
+//!
+//! Wraps `verify_verdict_proof` and `decode_output` from the host library.
+//! Every entry point returns an `i32` status code (see `include/aip_zkvm.h`)
+//! and catches panics, so no Rust unwinding crosses the FFI boundary.
+
+use std::ffi::{c_char, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use aip_zkvm_host::prover;
+use aip_zkvm_host::ProverError;
+
+/// Success.
+pub const AIP_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const AIP_ERR_NULL_POINTER: i32 = -1;
+/// The image ID string is not valid UTF-8 or not 64 hex characters.
+pub const AIP_ERR_INVALID_IMAGE_ID: i32 = -2;
+/// The receipt bytes do not deserialize as a receipt.
+pub const AIP_ERR_DESERIALIZE: i32 = -3;
+/// The proof did not verify against the image ID (or is a dev-mode receipt).
+pub const AIP_ERR_VERIFY: i32 = -4;
+/// The journal was committed under a schema version this build can't read.
+pub const AIP_ERR_SCHEMA_VERSION: i32 = -5;
+/// The journal does not decode as a `GuestOutput`.
+pub const AIP_ERR_DECODE_JOURNAL: i32 = -6;
+/// The output buffer is too small for the JSON and its NUL terminator.
+pub const AIP_ERR_BUFFER_TOO_SMALL: i32 = -7;
+/// Any other error, including a caught panic.
+pub const AIP_ERR_INTERNAL: i32 = -99;
+
+/// Verify a bincode-serialized receipt.
+///
+/// `image_id_hex` is a NUL-terminated 64-char hex image ID, or null to
+/// verify against the guest embedded in this library. Returns `AIP_OK` if
+/// the proof verifies and its journal decodes, else a negative error code.
+///
+/// # Safety
+///
+/// `receipt` must point to `receipt_len` readable bytes, and `image_id_hex`
+/// must be null or a valid NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn aip_verify_receipt(
+    receipt: *const u8,
+    receipt_len: usize,
+    image_id_hex: *const c_char,
+) -> i32 {
+    guarded(|| {
+        let receipt = read_receipt(receipt, receipt_len)?;
+        let verified = if image_id_hex.is_null() {
+            prover::verify_verdict_proof(&receipt)
+        } else {
+            let image_id_hex = CStr::from_ptr(image_id_hex)
+                .to_str()
+                .map_err(|_| AIP_ERR_INVALID_IMAGE_ID)?;
+            let image_id = prover::image_id_from_hex(image_id_hex).map_err(|e| error_code(&e))?;
+            prover::verify_verdict_proof_with_image_id(&receipt, image_id)
+        };
+        verified.map(|_| AIP_OK).map_err(|e| error_code(&e))
+    })
+}
+
+/// Decode a receipt's journal into JSON, written NUL-terminated to `out`.
+///
+/// The JSON object has the `GuestOutput` fields (`verdict`, `action`,
+/// `concerns_hash`, ...). This does NOT verify the proof; call
+/// `aip_verify_receipt` first. Returns the JSON length in bytes, excluding
+/// the NUL, or a negative error code. Passing a null `out` with
+/// `out_len == 0` returns the length without writing, so callers can size
+/// the buffer; a buffer shorter than length + 1 yields
+/// `AIP_ERR_BUFFER_TOO_SMALL`.
+///
+/// # Safety
+///
+/// `receipt` must point to `receipt_len` readable bytes, and `out` must be
+/// null or point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aip_decode_verdict(
+    receipt: *const u8,
+    receipt_len: usize,
+    out: *mut c_char,
+    out_len: usize,
+) -> i32 {
+    guarded(|| {
+        let receipt = read_receipt(receipt, receipt_len)?;
+        let output = prover::decode_output(&receipt).map_err(|e| error_code(&e))?;
+        let json = serde_json::to_string(&output).map_err(|_| AIP_ERR_INTERNAL)?;
+        let len = i32::try_from(json.len()).map_err(|_| AIP_ERR_INTERNAL)?;
+
+        if out.is_null() {
+            return if out_len == 0 { Ok(len) } else { Err(AIP_ERR_NULL_POINTER) };
+        }
+        if out_len <= json.len() {
+            return Err(AIP_ERR_BUFFER_TOO_SMALL);
+        }
+        let out = slice::from_raw_parts_mut(out.cast::<u8>(), out_len);
+        out[..json.len()].copy_from_slice(json.as_bytes());
+        out[json.len()] = 0;
+        Ok(len)
+    })
+}
+
+/// Run `f`, flattening its error and any panic into a status code.
+fn guarded(f: impl FnOnce() -> Result<i32, i32>) -> i32 {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(code)) | Ok(Err(code)) => code,
+        Err(_) => AIP_ERR_INTERNAL,
+    }
+}
+
+/// # Safety
+///
+/// As for the `receipt` arguments of the entry points.
+unsafe fn read_receipt(receipt: *const u8, receipt_len: usize) -> Result<risc0_zkvm::Receipt, i32> {
+    if receipt.is_null() {
+        return Err(AIP_ERR_NULL_POINTER);
+    }
+    let bytes = slice::from_raw_parts(receipt, receipt_len);
+    prover::receipt_from_bytes_compat(bytes).map_err(|e| error_code(&e))
+}
+
+/// Status code for a host library error.
+fn error_code(error: &ProverError) -> i32 {
+    match error {
+        ProverError::InvalidImageId(_) => AIP_ERR_INVALID_IMAGE_ID,
+        ProverError::Deserialize { .. } => AIP_ERR_DESERIALIZE,
+        ProverError::Verify(_) | ProverError::DevModeReceipt => AIP_ERR_VERIFY,
+        ProverError::UnsupportedSchemaVersion { .. } => AIP_ERR_SCHEMA_VERSION,
+        ProverError::DecodeJournal(_) => AIP_ERR_DECODE_JOURNAL,
+        _ => AIP_ERR_INTERNAL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aip_zkvm_core::{GuestOutput, JOURNAL_SCHEMA_VERSION};
+    use prover::sample_output;
+
+    /// Bincode bytes of a fake (unproven) receipt committing `output`.
+    fn fake_receipt_bytes(output: &GuestOutput) -> Vec<u8> {
+        let receipt = prover::fake_receipt(output, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+        prover::receipt_to_bytes(&receipt).unwrap()
+    }
+
+    #[test]
+    fn test_decode_verdict_sizes_and_writes_json() {
+        let bytes = fake_receipt_bytes(&sample_output());
+        let len = unsafe { aip_decode_verdict(bytes.as_ptr(), bytes.len(), std::ptr::null_mut(), 0) };
+        assert!(len > 0, "{len}");
+
+        let mut small = vec![0 as c_char; len as usize];
+        let code = unsafe { aip_decode_verdict(bytes.as_ptr(), bytes.len(), small.as_mut_ptr(), small.len()) };
+        assert_eq!(code, AIP_ERR_BUFFER_TOO_SMALL);
+
+        let mut out = vec![0 as c_char; len as usize + 1];
+        let written = unsafe { aip_decode_verdict(bytes.as_ptr(), bytes.len(), out.as_mut_ptr(), out.len()) };
+        assert_eq!(written, len);
+        let json = unsafe { CStr::from_ptr(out.as_ptr()) }.to_str().unwrap();
+        let decoded: GuestOutput = serde_json::from_str(json).unwrap();
+        assert_eq!(decoded, sample_output());
+    }
+
+    #[test]
+    fn test_decode_verdict_rejects_unknown_schema_version() {
+        let output = GuestOutput { schema_version: JOURNAL_SCHEMA_VERSION + 1, ..sample_output() };
+        let bytes = fake_receipt_bytes(&output);
+        let code = unsafe { aip_decode_verdict(bytes.as_ptr(), bytes.len(), std::ptr::null_mut(), 0) };
+        assert_eq!(code, AIP_ERR_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_verify_receipt_error_codes() {
+        let garbage = [1u8, 2, 3];
+        unsafe {
+            assert_eq!(aip_verify_receipt(std::ptr::null(), 0, std::ptr::null()), AIP_ERR_NULL_POINTER);
+            assert_eq!(aip_verify_receipt(garbage.as_ptr(), garbage.len(), std::ptr::null()), AIP_ERR_DESERIALIZE);
+        }
+
+        let bytes = fake_receipt_bytes(&sample_output());
+        let bad_id = c"zz";
+        unsafe {
+            assert_eq!(
+                aip_verify_receipt(bytes.as_ptr(), bytes.len(), bad_id.as_ptr()),
+                AIP_ERR_INVALID_IMAGE_ID
+            );
+            // A fake receipt carries no proof and must not verify outside dev mode.
+            if !risc0_zkvm::is_dev_mode() {
+                assert_eq!(aip_verify_receipt(bytes.as_ptr(), bytes.len(), std::ptr::null()), AIP_ERR_VERIFY);
+            }
+        }
+    }
+}
@@ -5,14 +5,21 @@
 //!
 //!   cargo bench --bench proving --features bench-proving
 //!
+//! With `BENCH_ASSERT=1` the proving and verification benchmarks also fail
+//! if their mean time exceeds the targets below (`PROVE_TARGET`,
+//! `VERIFY_TARGET`), so a nightly run catches regressions.
+//!
 //! Expected performance targets:
 //!   - prove_verdict_derivation: 3-15s per proof
 //!   - verify_verdict_proof:     <100ms
 //!   - receipt_serialization:    <10ms, receipt size 200KB-2MB
+//!   - receipt_compression:      zstd ratio reported (needs `compression`)
 //!   - peak_memory_proving:      <3GB RSS
+//!   - concern_hash_cycles:      guest user cycles per hash algorithm reported
+//!   - prove_cold_vs_warm:       warm beats cold by the image build time
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // ---------------------------------------------------------------------------
 // Test fixture JSON — same files used by the prover unit tests.
@@ -30,6 +37,42 @@ const CARD_HASH: &str = "b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e
 const VALUES_HASH: &str = "c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4";
 const MODEL: &str = "bench-model";
 
+/// Mean-time ceiling for one proof, enforced with `BENCH_ASSERT=1`.
+const PROVE_TARGET: Duration = Duration::from_secs(15);
+/// Mean-time ceiling for one verification, enforced with `BENCH_ASSERT=1`.
+const VERIFY_TARGET: Duration = Duration::from_millis(100);
+
+/// Mean time over every iteration criterion ran, warm-up included, for
+/// checking against a target. Fed from `Bencher::iter_custom`.
+#[derive(Default)]
+struct MeanTime {
+    iters: u64,
+    total: Duration,
+}
+
+impl MeanTime {
+    /// Run `f` `iters` times, recording and returning the elapsed time.
+    fn time(&mut self, iters: u64, mut f: impl FnMut()) -> Duration {
+        let start = Instant::now();
+        for _ in 0..iters {
+            f();
+        }
+        let elapsed = start.elapsed();
+        self.iters += iters;
+        self.total += elapsed;
+        elapsed
+    }
+
+    /// With `BENCH_ASSERT=1`, panic if the mean exceeds `target`.
+    fn check(&self, name: &str, target: Duration) {
+        if std::env::var("BENCH_ASSERT").map_or(true, |v| v != "1") || self.iters == 0 {
+            return;
+        }
+        let mean = self.total / self.iters as u32;
+        assert!(mean <= target, "{}: mean {:?} exceeds target {:?}", name, mean, target);
+    }
+}
+
 /// Helper: list of (label, json) fixture pairs for parameterized benchmarks.
 fn fixtures() -> Vec<(&'static str, &'static str)> {
     vec![
@@ -53,23 +96,27 @@ fn prove_verdict_derivation(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(60));
 
     for (label, json) in fixtures() {
+        let mut timing = MeanTime::default();
         group.bench_with_input(
             BenchmarkId::from_parameter(label),
             &json,
             |b, &json| {
-                b.iter(|| {
-                    let (receipt, output) = aip_zkvm_host::prover::prove_verdict_derivation(
-                        black_box(json),
-                        black_box(THINKING_HASH),
-                        black_box(CARD_HASH),
-                        black_box(VALUES_HASH),
-                        black_box(MODEL),
-                    )
-                    .expect("proving must succeed");
-                    black_box((&receipt, &output));
+                b.iter_custom(|iters| {
+                    timing.time(iters, || {
+                        let (receipt, output) = aip_zkvm_host::prover::prove_verdict_derivation(
+                            black_box(json),
+                            black_box(THINKING_HASH),
+                            black_box(CARD_HASH),
+                            black_box(VALUES_HASH),
+                            black_box(MODEL),
+                        )
+                        .expect("proving must succeed");
+                        black_box((&receipt, &output));
+                    })
                 });
             },
         );
+        timing.check(&format!("prove_verdict_derivation/{}", label), PROVE_TARGET);
     }
     group.finish();
 }
@@ -96,13 +143,17 @@ fn verify_verdict_proof(c: &mut Criterion) {
     group.sample_size(50);
     group.measurement_time(Duration::from_secs(10));
 
+    let mut timing = MeanTime::default();
     group.bench_function("clear_receipt", |b| {
-        b.iter(|| {
-            let output = aip_zkvm_host::prover::verify_verdict_proof(black_box(&receipt))
-                .expect("verification must succeed");
-            black_box(&output);
+        b.iter_custom(|iters| {
+            timing.time(iters, || {
+                let output = aip_zkvm_host::prover::verify_verdict_proof(black_box(&receipt))
+                    .expect("verification must succeed");
+                black_box(&output);
+            })
         });
     });
+    timing.check("verify_verdict_proof/clear_receipt", VERIFY_TARGET);
 
     group.finish();
 }
@@ -167,6 +218,56 @@ fn receipt_serialization_roundtrip(c: &mut Criterion) {
     group.finish();
 }
 
+// ---------------------------------------------------------------------------
+// 3b. receipt_compression
+//    zstd-compress a real receipt and report the size ratio.
+//    Only built with `--features bench-proving,compression`.
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "compression")]
+fn receipt_compression(c: &mut Criterion) {
+    let (receipt, _) = aip_zkvm_host::prover::prove_verdict_derivation(
+        CLEAR_JSON,
+        THINKING_HASH,
+        CARD_HASH,
+        VALUES_HASH,
+        MODEL,
+    )
+    .expect("setup: proving must succeed for compression benchmark");
+
+    let raw = aip_zkvm_host::prover::receipt_to_bytes(&receipt)
+        .expect("setup: serialization must succeed");
+    let compressed = aip_zkvm_host::prover::receipt_to_bytes_compressed(&receipt)
+        .expect("setup: compression must succeed");
+    eprintln!(
+        "[bench] receipt size: {} bytes raw, {} bytes zstd (ratio {:.2}x)",
+        raw.len(),
+        compressed.len(),
+        raw.len() as f64 / compressed.len() as f64
+    );
+
+    let mut group = c.benchmark_group("receipt_compression");
+    group.sample_size(20);
+    group.bench_function("compress", |b| {
+        b.iter(|| {
+            let bytes = aip_zkvm_host::prover::receipt_to_bytes_compressed(black_box(&receipt))
+                .expect("compression must succeed");
+            black_box(&bytes);
+        });
+    });
+    group.bench_function("decompress", |b| {
+        b.iter(|| {
+            let restored = aip_zkvm_host::prover::receipt_from_bytes_compressed(black_box(&compressed))
+                .expect("decompression must succeed");
+            black_box(&restored);
+        });
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "compression"))]
+fn receipt_compression(_c: &mut Criterion) {}
+
 // ---------------------------------------------------------------------------
 // 4. peak_memory_proving
 //    Measure peak resident set size (RSS) during a proving run.
@@ -217,6 +318,113 @@ fn peak_memory_proving(c: &mut Criterion) {
     group.finish();
 }
 
+// ---------------------------------------------------------------------------
+// 5. concern_hash_cycles
+//    Execute (not prove) the guest with each concern hash algorithm and
+//    report the user cycle count, so the SHA-256 vs BLAKE3 cost is visible.
+// ---------------------------------------------------------------------------
+
+fn concern_hash_cycles(c: &mut Criterion) {
+    use aip_zkvm_core::{GuestInput, HashAlgo};
+    use risc0_zkvm::{default_executor, ExecutorEnv};
+
+    let mut group = c.benchmark_group("concern_hash_cycles");
+    group.sample_size(10);
+
+    for algo in [HashAlgo::Sha256, HashAlgo::Blake3] {
+        let input = GuestInput {
+            schema_version: aip_zkvm_core::JOURNAL_SCHEMA_VERSION,
+            analysis_json: BOUNDARY_DECEPTION_JSON.to_string(),
+            thinking_hash: THINKING_HASH.to_string(),
+            card_hash: CARD_HASH.to_string(),
+            values_hash: VALUES_HASH.to_string(),
+            model: MODEL.to_string(),
+            policy: Default::default(),
+            hash_algo: algo,
+            max_evidence_length: aip_zkvm_core::MAX_EVIDENCE_LENGTH as u32,
+            assumptions: Vec::new(),
+        };
+
+        let execute = |input: &GuestInput| {
+            let env = ExecutorEnv::builder()
+                .write(input)
+                .expect("input must serialize")
+                .build()
+                .expect("env must build");
+            default_executor()
+                .execute(env, aip_zkvm_methods::AIP_ZKVM_GUEST_ELF)
+                .expect("execution must succeed")
+        };
+
+        // Execution is deterministic, so one untimed run gives the count.
+        eprintln!("[bench] {:?} user cycles: {}", algo, execute(&input).cycles());
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{:?}", algo)),
+            &input,
+            |b, input| b.iter(|| black_box(execute(input))),
+        );
+    }
+
+    group.finish();
+}
+
+// ---------------------------------------------------------------------------
+// 6. prove_cold_vs_warm
+//    "cold" proves straight from the ELF, rebuilding the guest memory image
+//    every time, as `LocalProver::prove` does; "warm" goes through
+//    `prove_from_input`, which reuses the image cached after the first
+//    proof. The difference is the per-proof setup the cache saves.
+// ---------------------------------------------------------------------------
+
+fn prove_cold_vs_warm(c: &mut Criterion) {
+    use aip_zkvm_core::GuestInput;
+    use risc0_zkvm::{ExecutorEnv, LocalProver, Prover};
+
+    let mut group = c.benchmark_group("prove_cold_vs_warm");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(60));
+
+    let input = GuestInput {
+        schema_version: aip_zkvm_core::JOURNAL_SCHEMA_VERSION,
+        analysis_json: CLEAR_JSON.to_string(),
+        thinking_hash: THINKING_HASH.to_string(),
+        card_hash: CARD_HASH.to_string(),
+        values_hash: VALUES_HASH.to_string(),
+        model: MODEL.to_string(),
+        policy: Default::default(),
+        hash_algo: Default::default(),
+        max_evidence_length: aip_zkvm_core::MAX_EVIDENCE_LENGTH as u32,
+        assumptions: Vec::new(),
+    };
+
+    group.bench_function("cold", |b| {
+        b.iter(|| {
+            let env = ExecutorEnv::builder()
+                .write(&input)
+                .expect("input must serialize")
+                .build()
+                .expect("env must build");
+            let info = LocalProver::new("cold")
+                .prove(env, aip_zkvm_methods::AIP_ZKVM_GUEST_ELF)
+                .expect("proving must succeed");
+            black_box(info);
+        });
+    });
+
+    // Build the cached image outside the measurement.
+    aip_zkvm_host::prover::prove_from_input(&input).expect("proving must succeed");
+    group.bench_function("warm", |b| {
+        b.iter(|| {
+            let proved = aip_zkvm_host::prover::prove_from_input(black_box(&input))
+                .expect("proving must succeed");
+            black_box(proved);
+        });
+    });
+
+    group.finish();
+}
+
 /// Read the current process peak RSS in bytes.
 ///
 /// - macOS: `ru_maxrss` is already in bytes.
@@ -260,7 +468,10 @@ criterion_group! {
         prove_verdict_derivation,
         verify_verdict_proof,
         receipt_serialization_roundtrip,
-        peak_memory_proving
+        receipt_compression,
+        peak_memory_proving,
+        concern_hash_cycles,
+        prove_cold_vs_warm
 }
 
 criterion_main!(proving_benches);
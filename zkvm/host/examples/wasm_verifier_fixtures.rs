@@ -0,0 +1,37 @@
+//! Generate the receipt fixtures the WASM verifier's tests check.
+//!
+//! Proves the `clear` fixture, compresses the receipt to succinct and then
+//! Groth16, and writes `succinct.bin`, `groth16.bin` and `image_id.txt` to
+//! the given directory. Needs the RISC Zero toolchain, and Docker for the
+//! Groth16 wrap. Rerun and commit the output whenever the guest ELF changes:
+//!
+//!   cargo run --example wasm_verifier_fixtures -- ../wasm-verifier/tests/fixtures
+
+use aip_zkvm_host::prover;
+use anyhow::{Context, Result};
+use risc0_zkvm::{default_prover, ProverOpts};
+use std::path::PathBuf;
+
+const CLEAR_JSON: &str = include_str!("../../tests/fixtures/clear.json");
+
+fn main() -> Result<()> {
+    let dir = PathBuf::from(
+        std::env::args()
+            .nth(1)
+            .context("usage: wasm_verifier_fixtures <output-dir>")?,
+    );
+    std::fs::create_dir_all(&dir)?;
+
+    let (receipt, _) = prover::prove_verdict_derivation(CLEAR_JSON, "", "", "", "test-model")?;
+    let succinct = default_prover().compress(&ProverOpts::succinct(), &receipt)?;
+    let groth16 = prover::compress_receipt(&succinct)?;
+
+    for (kind, receipt) in [("succinct", &succinct), ("groth16", &groth16)] {
+        prover::verify_verdict_proof(receipt)?;
+        let path = dir.join(format!("{kind}.bin"));
+        std::fs::write(&path, prover::receipt_to_bytes(receipt)?)?;
+        println!("Wrote {}", path.display());
+    }
+    std::fs::write(dir.join("image_id.txt"), format!("{}\n", prover::guest_image_id_hex()))?;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! The `.aipproof` container: a receipt plus the metadata needed to know
+//! what produced it without trying to verify it first.
+//!
+//! Layout (all integers little-endian):
+//!
+//! | bytes | field                                        |
+//! |-------|----------------------------------------------|
+//! | 8     | magic `AIPPROOF`                             |
+//! | 2     | format version (`BUNDLE_FORMAT_VERSION`)     |
+//! | 32    | guest image ID, words little-endian          |
+//! | 4     | journal schema version                       |
+//! | rest  | bincode receipt (as from `receipt_to_bytes`) |
+
+use aip_zkvm_core::JOURNAL_SCHEMA_VERSION;
+use risc0_zkvm::Receipt;
+
+use crate::error::ProverError;
+use crate::prover::{self, Result};
+
+/// Leading bytes of every `.aipproof` file.
+pub const BUNDLE_MAGIC: &[u8; 8] = b"AIPPROOF";
+
+/// Current container format version.
+pub const BUNDLE_FORMAT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = 8 + 2 + 32 + 4;
+
+/// A receipt with the image ID and journal schema version it was produced under.
+#[derive(Debug, Clone)]
+pub struct ProofBundle {
+    pub image_id: [u32; 8],
+    pub schema_version: u32,
+    pub receipt: Receipt,
+}
+
+impl ProofBundle {
+    /// Bundle a receipt produced by the built-in guest.
+    pub fn new(receipt: Receipt) -> Self {
+        Self {
+            image_id: aip_zkvm_methods::AIP_ZKVM_GUEST_ID,
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            receipt,
+        }
+    }
+}
+
+/// Whether `bytes` start with the `.aipproof` magic.
+pub fn is_bundle(bytes: &[u8]) -> bool {
+    bytes.starts_with(BUNDLE_MAGIC)
+}
+
+/// Serialize a bundle to `.aipproof` bytes.
+pub fn write_bundle(bundle: &ProofBundle) -> Result<Vec<u8>> {
+    let payload = prover::receipt_to_bytes(&bundle.receipt)?;
+    let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+    out.extend_from_slice(BUNDLE_MAGIC);
+    out.extend_from_slice(&BUNDLE_FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&prover::image_id_to_bytes(&bundle.image_id));
+    out.extend_from_slice(&bundle.schema_version.to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+/// Parse `.aipproof` bytes. This checks the header only; the receipt is not
+/// verified. Like other receipt readers it accepts any bincode configuration;
+/// see `prover::receipt_from_bytes_compat`.
+pub fn read_bundle(bytes: &[u8]) -> Result<ProofBundle> {
+    if bytes.len() < HEADER_LEN {
+        return Err(ProverError::Bundle(format!(
+            "too short: {} bytes, header is {}",
+            bytes.len(),
+            HEADER_LEN
+        )));
+    }
+    if !is_bundle(bytes) {
+        return Err(ProverError::Bundle("bad magic".to_string()));
+    }
+
+    let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+    if version != BUNDLE_FORMAT_VERSION {
+        return Err(ProverError::Bundle(format!(
+            "unsupported format version {} (expected {})",
+            version, BUNDLE_FORMAT_VERSION
+        )));
+    }
+
+    let image_id = prover::image_id_from_bytes(bytes[10..42].try_into().unwrap());
+    let schema_version = u32::from_le_bytes(bytes[42..46].try_into().unwrap());
+    let receipt = prover::receipt_from_bytes_compat(&bytes[HEADER_LEN..])?;
+
+    Ok(ProofBundle {
+        image_id,
+        schema_version,
+        receipt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use risc0_zkvm::{FakeReceipt, InnerReceipt, ReceiptClaim};
+
+    fn sample_bundle() -> ProofBundle {
+        let journal = vec![1u8, 2, 3, 4];
+        let claim = ReceiptClaim::ok(aip_zkvm_methods::AIP_ZKVM_GUEST_ID, journal.clone());
+        ProofBundle::new(Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal))
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let bundle = sample_bundle();
+        let bytes = write_bundle(&bundle).unwrap();
+        assert!(is_bundle(&bytes));
+
+        let read = read_bundle(&bytes).unwrap();
+        assert_eq!(read.image_id, bundle.image_id);
+        assert_eq!(read.schema_version, JOURNAL_SCHEMA_VERSION);
+        assert_eq!(read.receipt.journal.bytes, bundle.receipt.journal.bytes);
+    }
+
+    #[test]
+    fn test_bundle_accepts_other_bincode_configs() {
+        use bincode::Options;
+        let bundle = sample_bundle();
+        let mut bytes = write_bundle(&bundle).unwrap();
+        bytes.truncate(HEADER_LEN);
+        bytes.extend(bincode::DefaultOptions::new().serialize(&bundle.receipt).unwrap());
+
+        let read = read_bundle(&bytes).unwrap();
+        assert_eq!(read.receipt.journal.bytes, bundle.receipt.journal.bytes);
+    }
+
+    #[test]
+    fn test_bundle_header_layout() {
+        let bytes = write_bundle(&sample_bundle()).unwrap();
+        assert_eq!(&bytes[..8], b"AIPPROOF");
+        assert_eq!(&bytes[8..10], &[1, 0]);
+        assert_eq!(hex::encode(&bytes[10..42]), prover::guest_image_id_hex());
+    }
+
+    #[test]
+    fn test_bundle_bad_magic() {
+        let mut bytes = write_bundle(&sample_bundle()).unwrap();
+        bytes[0] = b'X';
+        let err = read_bundle(&bytes).unwrap_err();
+        assert!(err.to_string().contains("bad magic"), "{}", err);
+    }
+
+    #[test]
+    fn test_bundle_unsupported_version_and_truncation() {
+        let mut bytes = write_bundle(&sample_bundle()).unwrap();
+        bytes[8] = 99;
+        assert!(read_bundle(&bytes).unwrap_err().to_string().contains("version 99"));
+        assert!(read_bundle(&bytes[..HEADER_LEN - 1]).is_err());
+    }
+}
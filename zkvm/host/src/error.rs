@@ -0,0 +1,85 @@
+//! Error type for the host library.
+
+use crate::prover::ProverBackend;
+
+/// Boxed underlying cause, for sources of differing types (bincode, serde,
+/// RISC Zero's `anyhow` errors, ...).
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Errors returned by the prover and bundle APIs.
+///
+/// Display messages are one line; the underlying cause, where there is one,
+/// is available via `std::error::Error::source`.
+#[derive(Debug, thiserror::Error)]
+pub enum ProverError {
+    /// The analysis JSON does not parse; rejected before proving.
+    #[error("Analysis JSON does not parse as an AnalysisResponse")]
+    ParseAnalysis(#[source] serde_json::Error),
+    /// The analysis parses but fails `AnalysisResponse::validate`.
+    #[error("Analysis response is invalid")]
+    InvalidAnalysis(#[source] aip_zkvm_core::ValidationError),
+    /// A pass-through commitment fails `validate_hash_hex`; rejected before
+    /// proving.
+    #[error("Invalid {field}")]
+    InvalidCommitment {
+        field: &'static str,
+        #[source]
+        source: aip_zkvm_core::ValidationError,
+    },
+    #[error("Failed to serialize {what}")]
+    Serialize {
+        what: &'static str,
+        #[source]
+        source: BoxError,
+    },
+    #[error("Failed to deserialize {what}")]
+    Deserialize {
+        what: &'static str,
+        #[source]
+        source: BoxError,
+    },
+    #[error("Failed to generate proof ({backend:?} backend)")]
+    Prove {
+        backend: ProverBackend,
+        #[source]
+        source: BoxError,
+    },
+    #[error("Failed to compress receipt to Groth16")]
+    Compress(#[source] BoxError),
+    /// A dev-mode receipt was presented while dev mode is off.
+    #[error(
+        "Receipt was produced in RISC Zero dev mode and carries no proof; \
+         it can only be verified with RISC0_DEV_MODE=1"
+    )]
+    DevModeReceipt,
+    #[error("Receipt verification failed")]
+    Verify(#[source] BoxError),
+    /// The journal was committed under a layout this build cannot decode.
+    #[error("Unsupported journal schema version {found} (this build reads version {expected})")]
+    UnsupportedSchemaVersion { found: u32, expected: u32 },
+    #[error("Failed to decode journal output")]
+    DecodeJournal(#[source] BoxError),
+    #[error("Invalid image ID: {0}")]
+    InvalidImageId(String),
+    /// The embedded guest ELF can't be loaded, so nothing can be proven.
+    #[error("Guest program is not usable for proving: {0}")]
+    GuestUnavailable(String),
+    #[error("Guest ELF hash mismatch: expected {expected}, got {actual}")]
+    ElfMismatch { expected: String, actual: String },
+    #[error("Invalid .aipproof bundle: {0}")]
+    Bundle(String),
+    /// `GuestInput::assumptions` does not list exactly the assumption
+    /// receipts being proven with; rejected before proving.
+    #[error("Input lists {listed} assumptions but {receipts} assumption receipts were supplied (or they differ)")]
+    AssumptionMismatch { listed: usize, receipts: usize },
+    /// A batch was empty or over the size limit; rejected before proving.
+    #[error("Batch has {count} inputs; 1 to {max} are allowed")]
+    BatchSize { count: usize, max: usize },
+    /// An input of a batch failed its pre-proving checks.
+    #[error("Batch input {index} is invalid")]
+    BatchInput {
+        index: usize,
+        #[source]
+        source: Box<ProverError>,
+    },
+}
@@ -4,5 +4,12 @@
 //! providing a clean interface for generating and verifying STARK
 //! proofs of verdict derivation.
 
+pub mod bundle;
+pub mod error;
+pub mod metrics;
 pub mod prover;
+pub mod rate_limit;
 pub mod server;
+pub mod telemetry;
+
+pub use error::ProverError;
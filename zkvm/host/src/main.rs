@@ -1,13 +1,28 @@
 //! AIP ZK Prover — CLI and HTTP server
 //!
 //! Usage:
-//!   aip-prover prove --input <json-file> [options]
-//!   aip-prover verify --receipt <receipt-file>
-//!   aip-prover serve [--port <port>]
+//!   aip-prover prove [--input <json-file>|-] [--format bin|base64|hex] [options]   (stdin by default)
+//!   aip-prover derive [--input <json-file>|-] [--json]                              (no proof)
+//!   aip-prover verify --receipt <receipt-file> [--format ...] [--pinned <elf-sha256>] [--json]
+//!                     [--expect-verdict <verdict>] [--expect-action <action>]
+//!   aip-prover audit --receipt <receipt-file> [--input <json-file>|-]               (re-derive, no verification)
+//!   aip-prover batch-prove <input-dir> <output-dir> [--concurrency <n>]
+//!   aip-prover serve [--port <port>]                                               (WARMUP=1 pre-proves)
+//!   aip-prover warmup                                                              (prove a built-in fixture, print timing)
+//!   aip-prover info [--schema]
+//!   aip-prover schema [guest-output|analysis-response]
 
+use aip_zkvm_core::{Action, GuestOutput, Verdict, JOURNAL_SCHEMA_VERSION};
+use aip_zkvm_host::bundle::{is_bundle, read_bundle, write_bundle, ProofBundle};
+use aip_zkvm_host::prover::ReceiptInfo;
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use base64::Engine;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 #[derive(Parser)]
 #[command(name = "aip-prover", about = "AIP Zero-Knowledge Verdict Prover")]
@@ -16,12 +31,60 @@ struct Cli {
     command: Commands,
 }
 
+/// On-disk encoding of a receipt's bincode bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ReceiptFormat {
+    Bin,
+    Base64,
+    Hex,
+}
+
+impl ReceiptFormat {
+    fn encode(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            ReceiptFormat::Bin => bytes.to_vec(),
+            ReceiptFormat::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes).into_bytes(),
+            ReceiptFormat::Hex => hex::encode(bytes).into_bytes(),
+        }
+    }
+
+    fn decode(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            ReceiptFormat::Bin => Ok(data.to_vec()),
+            ReceiptFormat::Base64 => Ok(base64::engine::general_purpose::STANDARD.decode(data.trim_ascii())?),
+            ReceiptFormat::Hex => Ok(hex::decode(data.trim_ascii())?),
+        }
+    }
+
+    /// Guess the encoding of a receipt file. Bincode receipts are never all
+    /// printable, so text files are hex if every byte is a hex digit, else base64.
+    fn detect(data: &[u8]) -> Self {
+        let text = data.trim_ascii();
+        if text.is_empty() || !text.iter().all(|b| b.is_ascii_graphic()) {
+            ReceiptFormat::Bin
+        } else if text.iter().all(u8::is_ascii_hexdigit) {
+            ReceiptFormat::Hex
+        } else {
+            ReceiptFormat::Base64
+        }
+    }
+}
+
+/// Type whose JSON Schema `schema` prints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum SchemaType {
+    /// The decoded proof journal
+    GuestOutput,
+    /// The analysis JSON given to the prover
+    AnalysisResponse,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Generate a proof for an analysis response
     Prove {
-        /// Path to the analysis JSON file
-        #[arg(short, long)]
+        /// Path to the analysis JSON file, or `-` for stdin
+        #[arg(short, long, default_value = "-")]
         input: String,
         /// SHA-256 hash of the thinking block
         #[arg(long, default_value = "")]
@@ -35,16 +98,118 @@ enum Commands {
         /// Model identifier
         #[arg(long, default_value = "unknown")]
         model: String,
-        /// Output file for the receipt
+        /// Output file for the receipt; a `.aipproof` extension writes a
+        /// self-describing bundle instead of a bare receipt
         #[arg(short, long, default_value = "receipt.bin")]
         output: String,
+        /// Encoding of the receipt file
+        #[arg(long, value_enum, default_value_t = ReceiptFormat::Bin)]
+        format: ReceiptFormat,
+        /// Prove unparseable input as a `malformed` verdict instead of
+        /// rejecting it
+        #[arg(long)]
+        allow_malformed: bool,
+    },
+    /// Derive the verdict the guest would prove, without proving
+    ///
+    /// Runs the same derivation natively and instantly. No receipt is
+    /// produced, so the result is a preview, not evidence.
+    Derive {
+        /// Path to the analysis JSON file, or `-` for stdin
+        #[arg(short, long, default_value = "-")]
+        input: String,
+        /// SHA-256 hash of the thinking block
+        #[arg(long, default_value = "")]
+        thinking_hash: String,
+        /// SHA-256 hash of the alignment card
+        #[arg(long, default_value = "")]
+        card_hash: String,
+        /// SHA-256 hash of the conscience values
+        #[arg(long, default_value = "")]
+        values_hash: String,
+        /// Model identifier
+        #[arg(long, default_value = "unknown")]
+        model: String,
+        /// Print the would-be journal as a single JSON object
+        #[arg(long)]
+        json: bool,
     },
     /// Verify a STARK receipt
     Verify {
-        /// Path to the receipt binary file
+        /// Path to the receipt file: a raw receipt, verified against the
+        /// built-in guest, or an `.aipproof` bundle, verified against the
+        /// image ID it embeds
         #[arg(short, long)]
         receipt: String,
+        /// Encoding of the receipt file; detected from its contents if omitted
+        #[arg(long, value_enum)]
+        format: Option<ReceiptFormat>,
+        /// Expected SHA-256 of the guest ELF; verification fails if the
+        /// embedded guest does not match
+        #[arg(long)]
+        pinned: Option<String>,
+        /// Print a single JSON object instead of text; exits non-zero and
+        /// prints `{"valid": false, "error": ...}` on failure
+        #[arg(long)]
+        json: bool,
+        /// Fail unless the proven verdict is this one
+        /// (clear, review_needed, boundary_violation)
+        #[arg(long)]
+        expect_verdict: Option<Verdict>,
+        /// Fail unless the proven action is this one (continue,
+        /// log_and_continue, pause_for_review, deny_and_escalate)
+        #[arg(long)]
+        expect_action: Option<Action>,
+    },
+    /// Re-derive a receipt's journal from the original analysis and report
+    /// any field that differs
+    ///
+    /// Decodes the journal without verifying the proof; use `verify` for
+    /// that. Exits non-zero on any mismatch.
+    Audit {
+        /// Path to the receipt file (raw receipt or `.aipproof` bundle)
+        #[arg(short, long)]
+        receipt: String,
+        /// Path to the original analysis JSON file, or `-` for stdin
+        #[arg(short, long, default_value = "-")]
+        input: String,
+        /// Encoding of the receipt file; detected from its contents if omitted
+        #[arg(long, value_enum)]
+        format: Option<ReceiptFormat>,
     },
+    /// Prove every `*.json` in a directory, writing `<name>.receipt` files
+    BatchProve {
+        /// Directory of analysis JSON files
+        input_dir: PathBuf,
+        /// Directory for receipts and `failures.json`
+        output_dir: PathBuf,
+        /// Number of proofs to run at once
+        #[arg(long, default_value = "1")]
+        concurrency: usize,
+        /// Model identifier
+        #[arg(long, default_value = "unknown")]
+        model: String,
+        /// Prove unparseable files as a `malformed` verdict instead of
+        /// failing them
+        #[arg(long)]
+        allow_malformed: bool,
+    },
+    /// Print the crate version and guest image ID
+    Info {
+        /// Also print the journal (`GuestOutput`) schema
+        #[arg(long)]
+        schema: bool,
+    },
+    /// Print the JSON Schema of a journal or input type to stdout
+    Schema {
+        #[arg(value_enum, default_value_t = SchemaType::GuestOutput)]
+        r#type: SchemaType,
+    },
+    /// Prove a tiny built-in analysis and report how long it took
+    ///
+    /// The receipt is discarded. Useful for warming a fresh machine before
+    /// it takes traffic, or for a quick proving smoke test.
+    Warmup,
     /// Start the HTTP proving service
     Serve {
         /// Port to listen on
@@ -55,7 +220,13 @@ enum Commands {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+    // LOG_FORMAT=json emits one JSON object per event, with span and event
+    // fields (proof_id, verdict, duration_ms, ...) as structured keys.
+    // With the `otel` feature, OTEL_EXPORTER_OTLP_ENDPOINT also exports spans.
+    let telemetry = aip_zkvm_host::telemetry::init();
+    if telemetry.exporting() {
+        tracing::info!("Exporting spans via OTLP");
+    }
 
     let cli = Cli::parse();
 
@@ -67,69 +238,530 @@ async fn main() -> Result<()> {
             values_hash,
             model,
             output,
+            format,
+            allow_malformed,
         } => {
-            let analysis_json = fs::read_to_string(&input)?;
-            println!("Proving verdict derivation for: {}", input);
+            for (flag, hash) in [
+                ("--thinking-hash", &thinking_hash),
+                ("--card-hash", &card_hash),
+                ("--values-hash", &values_hash),
+            ] {
+                aip_zkvm_core::validate_hash_hex(hash).map_err(|e| anyhow::anyhow!("Invalid {}: {}", flag, e))?;
+            }
+            let analysis_json = read_input(&input)?;
+            println!("Proving verdict derivation for: {}", if input == "-" { "<stdin>" } else { &input });
 
-            let (receipt, guest_output) = aip_zkvm_host::prover::prove_verdict_derivation(
-                &analysis_json,
-                &thinking_hash,
-                &card_hash,
-                &values_hash,
-                &model,
-            )?;
+            let prove = if allow_malformed {
+                aip_zkvm_host::prover::prove_verdict_derivation_allowing_malformed
+            } else {
+                aip_zkvm_host::prover::prove_verdict_derivation
+            };
+            let (receipt, guest_output) = prove(&analysis_json, &thinking_hash, &card_hash, &values_hash, &model)?;
 
             println!("Verdict: {:?}", guest_output.verdict);
             println!("Action: {:?}", guest_output.action);
             println!("Concerns hash: {}", guest_output.concerns_hash);
 
-            let bytes = aip_zkvm_host::prover::receipt_to_bytes(&receipt)?;
+            let raw = if output.ends_with(".aipproof") {
+                write_bundle(&ProofBundle::new(receipt.clone()))?
+            } else {
+                aip_zkvm_host::prover::receipt_to_bytes(&receipt)?
+            };
+            let bytes = format.encode(&raw);
             fs::write(&output, &bytes)?;
-            println!("Receipt written to: {} ({} bytes)", output, bytes.len());
+            println!("Receipt written to: {} ({} bytes, {:?})", output, bytes.len(), format);
 
             // Self-verify
             let verified = aip_zkvm_host::prover::verify_verdict_proof(&receipt)?;
             println!("Self-verification: verdict={:?}, action={:?}", verified.verdict, verified.action);
         }
-        Commands::Verify { receipt: receipt_path } => {
-            let bytes = fs::read(&receipt_path)?;
-            println!("Verifying receipt: {} ({} bytes)", receipt_path, bytes.len());
+        Commands::Derive {
+            input,
+            thinking_hash,
+            card_hash,
+            values_hash,
+            model,
+            json,
+        } => {
+            let analysis_json = read_input(&input)?;
+            let output = aip_zkvm_host::prover::derive_output(
+                &analysis_json,
+                &thinking_hash,
+                &card_hash,
+                &values_hash,
+                &model,
+            )?;
+
+            if json {
+                println!("{}", serde_json::to_string(&output)?);
+            } else {
+                println!("Derived (not proven): {}", if input == "-" { "<stdin>" } else { &input });
+                println!("Verdict: {:?}", output.verdict);
+                println!("Action: {:?}", output.action);
+                println!("Confidence: {:.2}%", f64::from(output.confidence_bps) / 100.0);
+                println!("Concerns hash: {}", output.concerns_hash);
+                println!("Policy hash: {}", output.policy_hash);
+            }
+        }
+        Commands::Verify {
+            receipt: receipt_path,
+            format,
+            pinned,
+            json: true,
+            expect_verdict,
+            expect_action,
+        } => {
+            match verify_receipt_file(&receipt_path, format, pinned.as_deref()) {
+                Ok((output, image_id, info)) => {
+                    let mut report = serde_json::json!({
+                        "valid": true,
+                        "image_id": aip_zkvm_host::prover::image_id_to_hex(&image_id),
+                        "receipt_kind": info.kind,
+                        "receipt_bytes": info.byte_size,
+                        "schema_version": output.schema_version,
+                        "verdict": output.verdict.to_string(),
+                        "action": output.action.to_string(),
+                        "confidence_bps": output.confidence_bps,
+                        "concerns_hash": output.concerns_hash,
+                        "max_evidence_length": output.max_evidence_length,
+                        "policy_hash": output.policy_hash,
+                        "thinking_hash": output.thinking_hash,
+                        "card_hash": output.card_hash,
+                        "values_hash": output.values_hash,
+                        "model": output.model,
+                        "assumptions_hash": output.assumptions_hash,
+                        "stated_verdict_matches": output.stated_verdict_matches,
+                    });
+                    // The proof is valid either way; a mismatch is reported
+                    // alongside it and still fails the command.
+                    let mismatch = check_expectations(&output, expect_verdict, expect_action);
+                    if let Err(e) = &mismatch {
+                        report["error"] = serde_json::Value::String(e.to_string());
+                    }
+                    println!("{}", report);
+                    if mismatch.is_err() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    println!("{}", serde_json::json!({ "valid": false, "error": format!("{:#}", e) }));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Verify {
+            receipt: receipt_path,
+            format,
+            pinned,
+            json: false,
+            expect_verdict,
+            expect_action,
+        } => {
+            if let Some(expected) = &pinned {
+                aip_zkvm_host::prover::check_pinned_elf(expected)?;
+                println!("Guest ELF pinned: {}", expected);
+            }
 
-            let receipt = aip_zkvm_host::prover::receipt_from_bytes(&bytes)?;
-            let output = aip_zkvm_host::prover::verify_verdict_proof(&receipt)?;
+            println!("Verifying receipt: {}", receipt_path);
+            let (receipt, image_id) = read_receipt(&receipt_path, format)?;
+            let output = aip_zkvm_host::prover::verify_verdict_proof_with_image_id(&receipt, image_id)?;
+            let info = aip_zkvm_host::prover::receipt_info(&receipt)?;
 
             println!("Verification: PASSED");
+            println!("Image ID: {}", aip_zkvm_host::prover::image_id_to_hex(&image_id));
+            println!("Receipt: {} ({} bytes)", info.kind, info.byte_size);
+            println!("Schema version: {}", output.schema_version);
             println!("Verdict: {:?}", output.verdict);
             println!("Action: {:?}", output.action);
+            println!("Confidence: {:.2}%", f64::from(output.confidence_bps) / 100.0);
             println!("Concerns hash: {}", output.concerns_hash);
+            println!("Max evidence length: {}", output.max_evidence_length);
+            println!("Policy hash: {}", output.policy_hash);
             println!("Thinking hash: {}", output.thinking_hash);
             println!("Card hash: {}", output.card_hash);
             println!("Values hash: {}", output.values_hash);
             println!("Model: {}", output.model);
+            if !output.assumptions_hash.is_empty() {
+                println!("Assumptions hash: {}", output.assumptions_hash);
+            }
+            if !output.stated_verdict_matches {
+                println!("Stated verdict: does not match the derived verdict");
+            }
+
+            check_expectations(&output, expect_verdict, expect_action)?;
+        }
+        Commands::BatchProve {
+            input_dir,
+            output_dir,
+            concurrency,
+            model,
+            allow_malformed,
+        } => batch_prove(&input_dir, &output_dir, concurrency.max(1), &model, allow_malformed)?,
+        Commands::Audit {
+            receipt: receipt_path,
+            input,
+            format,
+        } => {
+            let (receipt, _) = read_receipt(&receipt_path, format)?;
+            let committed = aip_zkvm_host::prover::decode_output(&receipt)?;
+            let analysis_json = read_input(&input)?;
+            let mismatches = aip_zkvm_host::prover::audit_output(&committed, &analysis_json)?;
+
+            println!("Committed: verdict={}, action={}", committed.verdict, committed.action);
+            if mismatches.is_empty() {
+                println!("Audit: journal matches re-derivation");
+            } else {
+                for m in &mismatches {
+                    println!("Mismatch in {}: committed {}, derived {}", m.field, m.committed, m.derived);
+                }
+                anyhow::bail!("{} field(s) differ from re-derivation", mismatches.len());
+            }
+        }
+        Commands::Info { schema } => {
+            println!("Version: {}", env!("CARGO_PKG_VERSION"));
+            println!("Image ID: {}", aip_zkvm_host::prover::guest_image_id_hex());
+            println!("Guest ELF SHA-256: {}", aip_zkvm_host::prover::guest_elf_sha256());
+            if schema {
+                println!("Journal schema v{} (GuestOutput, in commit order):", JOURNAL_SCHEMA_VERSION);
+                println!("  schema_version u32; verifiers reject any other version");
+                println!("  verdict        clear | review_needed | boundary_violation | malformed");
+                println!("  action         continue | log_and_continue | pause_for_review | deny_and_escalate");
+                println!("  confidence_bps stated confidence, 0-10000 basis points");
+                println!("  concerns_hash  hex digest of the normalized concerns (of the raw input if malformed)");
+                println!("  hash_algo      sha256 | blake3");
+                println!("  max_evidence_length  evidence truncation, in bytes, behind concerns_hash");
+                println!("  policy_hash    SHA-256 of the verdict policy's canonical bytes");
+                println!("  thinking_hash  pass-through");
+                println!("  card_hash      pass-through");
+                println!("  values_hash    pass-through");
+                println!("  model          pass-through");
+                println!("  assumptions_hash  SHA-256 over the upstream proofs composed with; empty if none");
+                println!("  stated_verdict_matches  whether the analysis's own verdict names the derived one");
+            }
+        }
+        Commands::Schema { r#type } => {
+            let schema = match r#type {
+                SchemaType::GuestOutput => aip_zkvm_core::schema::guest_output_schema(),
+                SchemaType::AnalysisResponse => aip_zkvm_core::schema::analysis_response_schema(),
+            };
+            print!("{}", aip_zkvm_core::schema::to_pretty_json(&schema));
+        }
+        Commands::Warmup => {
+            let elapsed = aip_zkvm_host::prover::warm_up(&aip_zkvm_host::prover::RiscZeroProver)?;
+            println!("Warm-up proof took {:.2}s", elapsed.as_secs_f64());
         }
         Commands::Serve { port } => {
             let database_url = std::env::var("DATABASE_URL")
                 .expect("DATABASE_URL must be set");
             let prover_key = std::env::var("PROVER_API_KEY").ok();
+            let max_concurrent_proofs = std::env::var("MAX_CONCURRENT_PROOFS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(aip_zkvm_host::server::DEFAULT_MAX_CONCURRENT_PROOFS);
+            let max_body_bytes = std::env::var("MAX_REQUEST_BODY_BYTES")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(aip_zkvm_host::server::DEFAULT_MAX_BODY_BYTES);
+            let proof_timeout = std::env::var("PROOF_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|&n| n > 0)
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(aip_zkvm_host::server::DEFAULT_PROOF_TIMEOUT);
+            let self_verify = std::env::var("SELF_VERIFY")
+                .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off"))
+                .unwrap_or(true);
+            let warmup = std::env::var("WARMUP")
+                .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false);
+            let retry_policy = aip_zkvm_host::server::RetryPolicy::from_env().map_err(anyhow::Error::msg)?;
+
+            tracing::info!(
+                version = env!("CARGO_PKG_VERSION"),
+                image_id = %aip_zkvm_host::prover::guest_image_id_hex(),
+                guest_elf_sha256 = %aip_zkvm_host::prover::guest_elf_sha256(),
+                "Starting AIP prover service"
+            );
 
             let db = sqlx::PgPool::connect(&database_url).await?;
             tracing::info!("Connected to database");
 
             let state = aip_zkvm_host::server::AppState {
-                db: db.clone(),
+                db,
                 prover_key,
+                proving_permits: std::sync::Arc::new(tokio::sync::Semaphore::new(max_concurrent_proofs)),
+                max_body_bytes,
+                rate_limiter: aip_zkvm_host::rate_limit::RateLimiter::from_env().map(std::sync::Arc::new),
+                proof_timeout,
+                self_verify,
+                prover: std::sync::Arc::new(aip_zkvm_host::prover::RiscZeroProver),
+                metrics: std::sync::Arc::new(aip_zkvm_host::metrics::Metrics::default()),
             };
+            tracing::info!(max_concurrent_proofs, "Proving concurrency limit set");
+            tracing::info!(proof_timeout_secs = proof_timeout.as_secs(), "Proof timeout set");
+            if !self_verify {
+                tracing::info!("Self-verification disabled; proofs are stored unverified");
+            }
 
-            let app = aip_zkvm_host::server::build_router(state);
+            let app = aip_zkvm_host::server::build_router(state.clone());
+
+            if warmup {
+                tracing::info!("Warm-up enabled; proving a built-in fixture in the background");
+                tokio::spawn(aip_zkvm_host::server::warm_up_task(state.clone()));
+            }
 
             // Spawn retry loop
-            tokio::spawn(aip_zkvm_host::server::retry_loop(db));
+            tracing::info!(
+                interval_secs = retry_policy.interval.as_secs(),
+                batch_size = retry_policy.batch_size,
+                backoff_base_secs = retry_policy.backoff_base.as_secs(),
+                max_retries = retry_policy.max_retries,
+                "Retry policy set"
+            );
+            tokio::spawn(aip_zkvm_host::server::retry_loop(state, retry_policy));
+
+            let keepalive = aip_zkvm_host::server::KeepaliveConfig::from_env(port);
+            if keepalive.enabled {
+                tracing::info!(url = %keepalive.url, interval_secs = keepalive.interval.as_secs(), "Keepalive enabled");
+                tokio::spawn(aip_zkvm_host::server::keepalive_task(keepalive));
+            }
 
             let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
             tracing::info!("Prover service listening on port {}", port);
-            axum::serve(listener, app).await?;
+            // Connect info gives the rate limiter client IPs when auth is disabled.
+            axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await?;
         }
     }
 
     Ok(())
 }
+
+/// Read `path` to a string, treating `-` as stdin.
+fn read_input(path: &str) -> Result<String> {
+    if path == "-" {
+        Ok(io::read_to_string(io::stdin())?)
+    } else {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+/// Check the optional ELF pin, then load and verify a receipt file,
+/// returning the image ID it was verified against.
+fn verify_receipt_file(
+    path: &str,
+    format: Option<ReceiptFormat>,
+    pinned: Option<&str>,
+) -> Result<(GuestOutput, [u32; 8], ReceiptInfo)> {
+    if let Some(expected) = pinned {
+        aip_zkvm_host::prover::check_pinned_elf(expected)?;
+    }
+    let (receipt, image_id) = read_receipt(path, format)?;
+    let output = aip_zkvm_host::prover::verify_verdict_proof_with_image_id(&receipt, image_id)?;
+    Ok((output, image_id, aip_zkvm_host::prover::receipt_info(&receipt)?))
+}
+
+/// One entry in a batch's `failures.json`.
+#[derive(Serialize)]
+struct BatchFailure {
+    file: String,
+    error: String,
+}
+
+/// Prove each `*.json` in `input_dir` with up to `concurrency` proofs in
+/// flight. Failures don't stop the batch; they go to `failures.json`, and the
+/// command errors at the end if there were any.
+fn batch_prove(
+    input_dir: &Path,
+    output_dir: &Path,
+    concurrency: usize,
+    model: &str,
+    allow_malformed: bool,
+) -> Result<()> {
+    let mut inputs: Vec<PathBuf> = fs::read_dir(input_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    inputs.sort();
+    fs::create_dir_all(output_dir)?;
+    println!("Proving {} files from {} ({} at a time)", inputs.len(), input_dir.display(), concurrency);
+
+    let queue = Mutex::new(inputs.iter());
+    let results = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.min(inputs.len()) {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                let Some(path) = next else { break };
+                let result = prove_file(path, output_dir, model, allow_malformed);
+                results.lock().unwrap().push((path, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut failures = Vec::new();
+    println!("{:<40} {:<20} {:<20}", "FILE", "VERDICT", "ACTION");
+    for (path, result) in &results {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match result {
+            Ok(output) => println!("{:<40} {:<20} {:<20}", name, output.verdict, output.action),
+            Err(e) => {
+                println!("{:<40} {:<20} {:<20}", name, "FAILED", "-");
+                failures.push(BatchFailure {
+                    file: name.into_owned(),
+                    error: format!("{:#}", e),
+                });
+            }
+        }
+    }
+
+    let manifest = output_dir.join("failures.json");
+    fs::write(&manifest, serde_json::to_vec_pretty(&failures)?)?;
+    println!("{} proved, {} failed", results.len() - failures.len(), failures.len());
+
+    if !failures.is_empty() {
+        anyhow::bail!("{} of {} proofs failed; see {}", failures.len(), results.len(), manifest.display());
+    }
+    Ok(())
+}
+
+/// Prove one analysis file and write `<stem>.receipt` into `output_dir`.
+fn prove_file(path: &Path, output_dir: &Path, model: &str, allow_malformed: bool) -> Result<GuestOutput> {
+    let analysis_json = fs::read_to_string(path)?;
+    let prove = if allow_malformed {
+        aip_zkvm_host::prover::prove_verdict_derivation_allowing_malformed
+    } else {
+        aip_zkvm_host::prover::prove_verdict_derivation
+    };
+    let (receipt, output) = prove(&analysis_json, "", "", "", model)?;
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let bytes = aip_zkvm_host::prover::receipt_to_bytes(&receipt)?;
+    fs::write(output_dir.join(format!("{}.receipt", stem)), bytes)?;
+    Ok(output)
+}
+
+/// Compare a verified journal against `--expect-verdict` / `--expect-action`.
+fn check_expectations(output: &GuestOutput, verdict: Option<Verdict>, action: Option<Action>) -> Result<()> {
+    if let Some(expected) = verdict {
+        if output.verdict != expected {
+            anyhow::bail!("Expected verdict {} but the proof commits to {}", expected, output.verdict);
+        }
+    }
+    if let Some(expected) = action {
+        if output.action != expected {
+            anyhow::bail!("Expected action {} but the proof commits to {}", expected, output.action);
+        }
+    }
+    Ok(())
+}
+
+/// Read a raw receipt or `.aipproof` bundle, returning the receipt and the
+/// image ID to verify it against: the bundle's embedded ID, or the built-in
+/// guest's for a raw receipt.
+fn read_receipt(path: &str, format: Option<ReceiptFormat>) -> Result<(risc0_zkvm::Receipt, [u32; 8])> {
+    let bytes = read_receipt_bytes(path, format)?;
+    if !is_bundle(&bytes) {
+        let receipt = aip_zkvm_host::prover::receipt_from_bytes_compat(&bytes)?;
+        return Ok((receipt, aip_zkvm_methods::AIP_ZKVM_GUEST_ID));
+    }
+
+    let bundle = read_bundle(&bytes)?;
+    if bundle.image_id != aip_zkvm_methods::AIP_ZKVM_GUEST_ID {
+        eprintln!(
+            "Warning: bundle was produced by image ID {}, not this build's {}; verifying against the bundle's",
+            aip_zkvm_host::prover::image_id_to_hex(&bundle.image_id),
+            aip_zkvm_host::prover::guest_image_id_hex()
+        );
+    }
+    if bundle.schema_version != JOURNAL_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Bundle journal schema version {} is not supported (expected {})",
+            bundle.schema_version,
+            JOURNAL_SCHEMA_VERSION
+        );
+    }
+    Ok((bundle.receipt, bundle.image_id))
+}
+
+/// Read a receipt file and decode it to bincode bytes, detecting the
+/// encoding when `format` is `None`.
+fn read_receipt_bytes(path: &str, format: Option<ReceiptFormat>) -> Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    let format = format.unwrap_or_else(|| ReceiptFormat::detect(&data));
+    format.decode(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_format_roundtrip_and_detect() {
+        let bytes = [0u8, 1, 2, 0xfe, 0xff, b'a'];
+        for format in [ReceiptFormat::Bin, ReceiptFormat::Base64, ReceiptFormat::Hex] {
+            let encoded = format.encode(&bytes);
+            assert_eq!(ReceiptFormat::detect(&encoded), format);
+            assert_eq!(format.decode(&encoded).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_check_expectations() {
+        let output = GuestOutput {
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            verdict: Verdict::ReviewNeeded,
+            action: Action::LogAndContinue,
+            confidence_bps: 9_500,
+            concerns_hash: String::new(),
+            hash_algo: Default::default(),
+            max_evidence_length: aip_zkvm_core::MAX_EVIDENCE_LENGTH as u32,
+            policy_hash: String::new(),
+            thinking_hash: String::new(),
+            card_hash: String::new(),
+            values_hash: String::new(),
+            model: String::new(),
+            assumptions_hash: String::new(),
+            stated_verdict_matches: true,
+        };
+        assert!(check_expectations(&output, None, None).is_ok());
+        assert!(check_expectations(&output, Some(Verdict::ReviewNeeded), Some(Action::LogAndContinue)).is_ok());
+        let err = check_expectations(&output, Some(Verdict::Clear), None).unwrap_err();
+        assert_eq!(err.to_string(), "Expected verdict clear but the proof commits to review_needed");
+        assert!(check_expectations(&output, None, Some(Action::Continue)).is_err());
+    }
+
+    #[test]
+    fn test_receipt_format_tolerates_trailing_newline() {
+        assert_eq!(ReceiptFormat::Hex.decode(b"00ff\n").unwrap(), [0x00, 0xff]);
+        assert_eq!(ReceiptFormat::detect(b"AAEC\n"), ReceiptFormat::Hex);
+        assert_eq!(ReceiptFormat::detect(b"AAE=\n"), ReceiptFormat::Base64);
+    }
+
+    #[test]
+    fn test_read_receipt_uses_bundle_image_id() {
+        use risc0_zkvm::{FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+        let other_id = [7u32; 8];
+        let claim = ReceiptClaim::ok(other_id, Vec::new());
+        let receipt = Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), Vec::new());
+        let dir = std::env::temp_dir().join(format!("aip-read-receipt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let bundle_path = dir.join("old.aipproof");
+        let bundle = ProofBundle { image_id: other_id, ..ProofBundle::new(receipt.clone()) };
+        fs::write(&bundle_path, write_bundle(&bundle).unwrap()).unwrap();
+        let (_, image_id) = read_receipt(bundle_path.to_str().unwrap(), None).unwrap();
+        assert_eq!(image_id, other_id);
+
+        let raw_path = dir.join("raw.receipt");
+        fs::write(&raw_path, aip_zkvm_host::prover::receipt_to_bytes(&receipt).unwrap()).unwrap();
+        let (_, image_id) = read_receipt(raw_path.to_str().unwrap(), None).unwrap();
+        assert_eq!(image_id, aip_zkvm_methods::AIP_ZKVM_GUEST_ID);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+//! Gauges for the prover service, served as Prometheus text on `/metrics`.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Sentinel for a gauge that has not been set yet; such gauges are omitted
+/// from the output rather than reported as 0.
+const UNSET: i64 = i64::MIN;
+
+/// Service gauges, updated in place and read by `render`.
+#[derive(Debug)]
+pub struct Metrics {
+    retry_loop_last_run: AtomicI64,
+    pending_proofs: AtomicI64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            retry_loop_last_run: AtomicI64::new(UNSET),
+            pending_proofs: AtomicI64::new(UNSET),
+        }
+    }
+}
+
+impl Metrics {
+    /// Record a completed retry-loop pass at `now_unix` (seconds), and the
+    /// pending-proof count it observed, if the count query succeeded.
+    pub fn record_retry_pass(&self, now_unix: i64, pending: Option<i64>) {
+        self.retry_loop_last_run.store(now_unix, Ordering::Relaxed);
+        if let Some(pending) = pending {
+            self.pending_proofs.store(pending, Ordering::Relaxed);
+        }
+    }
+
+    /// Prometheus text exposition format (version 0.0.4).
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut gauge = |name: &str, help: &str, value: i64| {
+            if value != UNSET {
+                let _ = write!(out, "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n");
+            }
+        };
+        gauge(
+            "aip_retry_loop_last_run_timestamp_seconds",
+            "Unix time the retry loop last completed a pass.",
+            self.retry_loop_last_run.load(Ordering::Relaxed),
+        );
+        gauge(
+            "aip_pending_proofs",
+            "Proofs in 'pending' status as of the last retry loop pass.",
+            self.pending_proofs.load(Ordering::Relaxed),
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_omits_unset_gauges() {
+        let metrics = Metrics::default();
+        assert_eq!(metrics.render(), "");
+
+        metrics.record_retry_pass(1_700_000_000, None);
+        let text = metrics.render();
+        assert!(text.contains("aip_retry_loop_last_run_timestamp_seconds 1700000000\n"));
+        assert!(!text.contains("aip_pending_proofs"));
+
+        metrics.record_retry_pass(1_700_000_030, Some(4));
+        let text = metrics.render();
+        assert!(text.contains("# TYPE aip_pending_proofs gauge\naip_pending_proofs 4\n"));
+        assert!(text.contains("aip_retry_loop_last_run_timestamp_seconds 1700000030\n"));
+    }
+}
@@ -1,13 +1,64 @@
 //! Proving and verification functions.
 
-use aip_zkvm_core::{GuestInput, GuestOutput};
-use aip_zkvm_methods::AIP_ZKVM_GUEST_ELF;
-use anyhow::{Context, Result};
-use risc0_zkvm::{default_prover, ExecutorEnv, Receipt};
+use aip_zkvm_core::{
+    evaluate, extract_json, journal_schema_version, AnalysisResponse, Assumption, GuestInput,
+    GuestInputBatch, GuestOutput, GuestOutputBatch, JOURNAL_SCHEMA_VERSION, MAX_BATCH_SIZE,
+    MAX_EVIDENCE_LENGTH, validate_hash_hex,
+};
+use aip_zkvm_methods::{AIP_ZKVM_GUEST_BATCH_ELF, AIP_ZKVM_GUEST_ELF};
+use crate::error::ProverError;
+use risc0_binfmt::{MemoryImage, Program};
+use risc0_zkvm::{
+    default_prover, get_prover_server, is_dev_mode, BonsaiProver, ExecutorEnv, ExecutorImpl,
+    FakeReceipt, InnerReceipt, ProveInfo, Prover as ZkvmProver, ProverOpts, Receipt, ReceiptClaim, VerifierContext, GUEST_MAX_MEM, PAGE_SIZE,
+};
+use std::rc::Rc;
+use std::sync::OnceLock;
+use sha2::{Digest, Sha256};
+
+/// Result type of the prover API.
+pub type Result<T, E = ProverError> = std::result::Result<T, E>;
+
+/// Backend used to generate proofs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProverBackend {
+    /// Whatever `default_prover()` selects — local proving in this build.
+    Local,
+    /// Remote proving on Bonsai, configured by `BONSAI_API_URL`/`BONSAI_API_KEY`.
+    Bonsai,
+}
+
+impl ProverBackend {
+    /// Pick Bonsai when both `BONSAI_API_URL` and `BONSAI_API_KEY` are set,
+    /// otherwise fall back to local proving. Dev mode always proves locally,
+    /// since Bonsai does not issue fake receipts.
+    pub fn from_env() -> Self {
+        let configured = |var| std::env::var(var).is_ok_and(|v| !v.is_empty());
+        if !is_dev_mode() && configured("BONSAI_API_URL") && configured("BONSAI_API_KEY") {
+            ProverBackend::Bonsai
+        } else {
+            ProverBackend::Local
+        }
+    }
+
+    fn prover(self) -> Rc<dyn ZkvmProver> {
+        match self {
+            ProverBackend::Local => default_prover(),
+            ProverBackend::Bonsai => Rc::new(BonsaiProver::new("bonsai")),
+        }
+    }
+}
 
 /// Prove that the verdict was correctly derived from the analysis.
 ///
+/// Thin wrapper around `prove_from_input`; the backend is chosen by
+/// `ProverBackend::from_env()`.
 /// Returns the STARK receipt and the committed guest output.
+///
+/// In RISC Zero dev mode (`RISC0_DEV_MODE=1`) the guest still executes, so the
+/// verdict and action are derived exactly as in a real proof, but the receipt
+/// is a fake with no cryptographic seal. Such receipts only verify while dev
+/// mode is active.
 pub fn prove_verdict_derivation(
     analysis_json: &str,
     thinking_hash: &str,
@@ -15,59 +66,1028 @@ pub fn prove_verdict_derivation(
     values_hash: &str,
     model: &str,
 ) -> Result<(Receipt, GuestOutput)> {
-    let input = GuestInput {
+    prove_from_input(&guest_input(analysis_json, thinking_hash, card_hash, values_hash, model))
+}
+
+/// Prove verdict derivation for an already-parsed `AnalysisResponse`.
+///
+/// The response is serialized to compact JSON (fields in declaration order)
+/// and fed to the guest as `analysis_json`, so test harnesses can build
+/// responses programmatically instead of hand-writing JSON.
+pub fn prove_analysis(
+    response: &AnalysisResponse,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> Result<(Receipt, GuestOutput)> {
+    let analysis_json = analysis_to_json(response)?;
+    prove_verdict_derivation(&analysis_json, thinking_hash, card_hash, values_hash, model)
+}
+
+fn analysis_to_json(response: &AnalysisResponse) -> Result<String> {
+    serde_json::to_string(response).map_err(|e| ProverError::Serialize {
+        what: "analysis response",
+        source: e.into(),
+    })
+}
+
+/// Prove verdict derivation from an already-assembled `GuestInput`.
+pub fn prove_from_input(input: &GuestInput) -> Result<(Receipt, GuestOutput)> {
+    prove_from_input_with_backend(ProverBackend::from_env(), input)
+}
+
+/// Cycle and segment counts reported by the prover for one proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProveStats {
+    /// Cycles spent executing guest code.
+    pub user_cycles: u64,
+    /// All cycles, including paging and padding to segment size.
+    pub total_cycles: u64,
+    /// Number of segments the execution was split into.
+    pub segments: usize,
+}
+
+/// Like `prove_verdict_derivation`, but also returns the prover's cycle and
+/// segment counts so callers can watch the ~10K cycle budget.
+pub fn prove_verdict_derivation_with_stats(
+    analysis_json: &str,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> Result<(Receipt, GuestOutput, ProveStats)> {
+    prove_input_with_stats(
+        ProverBackend::from_env(),
+        &guest_input(analysis_json, thinking_hash, card_hash, values_hash, model),
+    )
+}
+
+/// Like `prove_verdict_derivation`, but analysis JSON that does not parse is
+/// proven instead of rejected: the guest commits `Verdict::Malformed` with
+/// `concerns_hash` set to `hash_raw_analysis` of the input. Analyses that
+/// parse but fail validation are still rejected up front.
+pub fn prove_verdict_derivation_allowing_malformed(
+    analysis_json: &str,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> Result<(Receipt, GuestOutput)> {
+    let (receipt, output, _) = prove_verdict_derivation_allowing_malformed_with_stats(
+        analysis_json,
+        thinking_hash,
+        card_hash,
+        values_hash,
+        model,
+    )?;
+    Ok((receipt, output))
+}
+
+/// `prove_verdict_derivation_allowing_malformed` with the prover's cycle and
+/// segment counts, as `prove_verdict_derivation_with_stats` reports them.
+pub fn prove_verdict_derivation_allowing_malformed_with_stats(
+    analysis_json: &str,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> Result<(Receipt, GuestOutput, ProveStats)> {
+    let input = guest_input(analysis_json, thinking_hash, card_hash, values_hash, model);
+    check_analysis_allowing_malformed(&input.analysis_json)?;
+    check_commitments(&input)?;
+    execute_and_prove(ProverBackend::from_env(), &input, Vec::new())
+}
+
+/// Prove verdict derivation on an explicitly chosen backend.
+pub fn prove_verdict_derivation_with_backend(
+    backend: ProverBackend,
+    analysis_json: &str,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> Result<(Receipt, GuestOutput)> {
+    prove_from_input_with_backend(
+        backend,
+        &guest_input(analysis_json, thinking_hash, card_hash, values_hash, model),
+    )
+}
+
+/// Prove verdict derivation from a `GuestInput` on an explicitly chosen backend.
+pub fn prove_from_input_with_backend(
+    backend: ProverBackend,
+    input: &GuestInput,
+) -> Result<(Receipt, GuestOutput)> {
+    let (receipt, output, _) = prove_input_with_stats(backend, input)?;
+    Ok((receipt, output))
+}
+
+/// Proving and verification as the HTTP service uses them, so its request
+/// handling can be tested without the RISC Zero toolchain.
+pub trait Prover: Send + Sync {
+    /// Prove verdict derivation; see
+    /// `prove_verdict_derivation_allowing_malformed_with_stats`. Unparseable
+    /// analysis JSON is proven as `Verdict::Malformed`, not rejected.
+    fn prove(
+        &self,
+        analysis_json: &str,
+        thinking_hash: &str,
+        card_hash: &str,
+        values_hash: &str,
+        model: &str,
+    ) -> Result<(Receipt, GuestOutput, ProveStats)>;
+
+    /// Verify `receipt` against `image_id`; see
+    /// `verify_verdict_proof_with_image_id`.
+    fn verify(&self, receipt: &Receipt, image_id: [u32; 8]) -> Result<GuestOutput>;
+}
+
+/// The real `Prover`: the embedded guest on `ProverBackend::from_env()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RiscZeroProver;
+
+impl Prover for RiscZeroProver {
+    fn prove(
+        &self,
+        analysis_json: &str,
+        thinking_hash: &str,
+        card_hash: &str,
+        values_hash: &str,
+        model: &str,
+    ) -> Result<(Receipt, GuestOutput, ProveStats)> {
+        prove_verdict_derivation_allowing_malformed_with_stats(analysis_json, thinking_hash, card_hash, values_hash, model)
+    }
+
+    fn verify(&self, receipt: &Receipt, image_id: [u32; 8]) -> Result<GuestOutput> {
+        verify_verdict_proof_with_image_id(receipt, image_id)
+    }
+}
+
+/// Analysis proved by `warm_up`: the smallest `clear` fixture.
+const WARMUP_ANALYSIS_JSON: &str = include_str!("../../tests/fixtures/clear.json");
+
+/// Prove a tiny built-in analysis with `prover` and discard the receipt, so
+/// the first real proof after a cold start finds the allocator, page cache
+/// and guest image already warm. Returns how long the warm-up proof took.
+pub fn warm_up(prover: &dyn Prover) -> Result<std::time::Duration> {
+    let start = std::time::Instant::now();
+    prover.prove(WARMUP_ANALYSIS_JSON, "", "", "", "warmup")?;
+    Ok(start.elapsed())
+}
+
+/// A `Prover` for tests that never runs the guest.
+///
+/// `prove` runs the same checks as the real prover, derives the output
+/// natively (`evaluate`) and returns it in a fake receipt with
+/// zero stats. `verify` accepts any receipt whose claim is for `image_id`
+/// and decodes its journal, so it proves nothing: never use it to serve.
+#[derive(Debug, Default)]
+pub struct MockProver {
+    /// Number of `prove` calls so far.
+    pub proofs: std::sync::atomic::AtomicUsize,
+}
+
+impl Prover for MockProver {
+    fn prove(
+        &self,
+        analysis_json: &str,
+        thinking_hash: &str,
+        card_hash: &str,
+        values_hash: &str,
+        model: &str,
+    ) -> Result<(Receipt, GuestOutput, ProveStats)> {
+        self.proofs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let input = guest_input(analysis_json, thinking_hash, card_hash, values_hash, model);
+        check_analysis_allowing_malformed(&input.analysis_json)?;
+        check_commitments(&input)?;
+        let output = evaluate(&input).map_err(ProverError::InvalidAnalysis)?;
+        let receipt = fake_receipt(&output, aip_zkvm_methods::AIP_ZKVM_GUEST_ID)?;
+        Ok((receipt, output, ProveStats::default()))
+    }
+
+    fn verify(&self, receipt: &Receipt, image_id: [u32; 8]) -> Result<GuestOutput> {
+        if assumption_from_receipt(receipt)?.image_id != image_id {
+            return Err(ProverError::Verify("receipt is for another image ID".into()));
+        }
+        decode_output(receipt)
+    }
+}
+
+/// An unproven receipt committing `output` for `image_id`, as dev mode
+/// would produce. It only verifies while dev mode is active.
+pub fn fake_receipt(output: &impl serde::Serialize, image_id: [u32; 8]) -> Result<Receipt> {
+    let words = risc0_zkvm::serde::to_vec(output).map_err(|e| ProverError::Serialize {
+        what: "journal",
+        source: e.into(),
+    })?;
+    let journal: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    let claim = ReceiptClaim::ok(image_id, journal.clone());
+    Ok(Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal))
+}
+
+/// A plausible `GuestOutput` for tests, with every field set. Pair it with
+/// `fake_receipt` to get a receipt without proving.
+#[cfg(any(test, feature = "test-util"))]
+pub fn sample_output() -> GuestOutput {
+    GuestOutput {
+        schema_version: JOURNAL_SCHEMA_VERSION,
+        verdict: aip_zkvm_core::Verdict::ReviewNeeded,
+        action: aip_zkvm_core::Action::LogAndContinue,
+        confidence_bps: 9_500,
+        concerns_hash: "c".repeat(64),
+        hash_algo: Default::default(),
+        max_evidence_length: MAX_EVIDENCE_LENGTH as u32,
+        policy_hash: aip_zkvm_core::VerdictPolicy::default().policy_hash(),
+        thinking_hash: "a1".repeat(32),
+        card_hash: "b2".repeat(32),
+        values_hash: "c3".repeat(32),
+        model: "test-model".to_string(),
+        assumptions_hash: String::new(),
+        stated_verdict_matches: true,
+    }
+}
+
+/// Storage for receipts keyed by `input_hash`, consulted by
+/// `prove_from_input_cached` before proving.
+pub trait ReceiptCache: Send + Sync {
+    fn get(&self, input_hash: &str) -> Option<Receipt>;
+    fn put(&self, input_hash: &str, receipt: &Receipt);
+}
+
+/// Unbounded in-process `ReceiptCache`.
+#[derive(Default)]
+pub struct InMemoryReceiptCache {
+    receipts: std::sync::Mutex<std::collections::HashMap<String, Receipt>>,
+}
+
+impl ReceiptCache for InMemoryReceiptCache {
+    fn get(&self, input_hash: &str) -> Option<Receipt> {
+        self.receipts.lock().unwrap_or_else(|e| e.into_inner()).get(input_hash).cloned()
+    }
+
+    fn put(&self, input_hash: &str, receipt: &Receipt) {
+        self.receipts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(input_hash.to_string(), receipt.clone());
+    }
+}
+
+/// Hex SHA-256 identifying a `GuestInput`: identical inputs prove to identical
+/// outputs, so this is the cache key for `ReceiptCache`.
+///
+/// Hashes `GuestInput::canonical_bytes`.
+pub fn input_hash(input: &GuestInput) -> String {
+    hex::encode(Sha256::digest(input.canonical_bytes()))
+}
+
+/// Like `prove_from_input`, but returns the cached receipt when `cache`
+/// already holds one for this exact input, and caches fresh proofs.
+pub fn prove_from_input_cached(input: &GuestInput, cache: &dyn ReceiptCache) -> Result<(Receipt, GuestOutput)> {
+    cached_prove(input, cache, prove_from_input)
+}
+
+fn cached_prove(
+    input: &GuestInput,
+    cache: &dyn ReceiptCache,
+    prove: impl FnOnce(&GuestInput) -> Result<(Receipt, GuestOutput)>,
+) -> Result<(Receipt, GuestOutput)> {
+    let key = input_hash(input);
+    if let Some(receipt) = cache.get(&key) {
+        let output = decode_output(&receipt)?;
+        return Ok((receipt, output));
+    }
+
+    let (receipt, output) = prove(input)?;
+    cache.put(&key, &receipt);
+    Ok((receipt, output))
+}
+
+fn guest_input(
+    analysis_json: &str,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> GuestInput {
+    GuestInput {
+        schema_version: JOURNAL_SCHEMA_VERSION,
         analysis_json: analysis_json.to_string(),
         thinking_hash: thinking_hash.to_string(),
         card_hash: card_hash.to_string(),
         values_hash: values_hash.to_string(),
         model: model.to_string(),
+        policy: Default::default(),
+        hash_algo: Default::default(),
+        max_evidence_length: MAX_EVIDENCE_LENGTH as u32,
+        assumptions: Vec::new(),
+    }
+}
+
+/// Derive the `GuestOutput` the guest would commit, without proving.
+///
+/// Runs the guest's derivation (`aip_zkvm_core::evaluate`) natively, so it
+/// is instant but produces no receipt: the result is a preview, not evidence.
+/// Input the guest would reject is rejected here with the same error as
+/// `prove_verdict_derivation`.
+pub fn derive_output(
+    analysis_json: &str,
+    thinking_hash: &str,
+    card_hash: &str,
+    values_hash: &str,
+    model: &str,
+) -> Result<GuestOutput> {
+    derive_from_input(&guest_input(analysis_json, thinking_hash, card_hash, values_hash, model))
+}
+
+/// Like `derive_output`, from an already-assembled `GuestInput`.
+pub fn derive_from_input(input: &GuestInput) -> Result<GuestOutput> {
+    check_analysis(&input.analysis_json)?;
+    evaluate(input).map_err(ProverError::InvalidAnalysis)
+}
+
+/// A journal field that re-derivation from the original analysis disagrees
+/// with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditMismatch {
+    pub field: &'static str,
+    pub committed: String,
+    pub derived: String,
+}
+
+/// Re-derive `committed` from the original `analysis_json` and list every
+/// derived field that differs; empty means the journal matches.
+///
+/// The pass-through hashes, model, hash algorithm and evidence length are
+/// taken from the journal, so derivation sees what the guest saw. Only the
+/// verdict policy's hash is committed, so the default policy is assumed; a
+/// proof made under another policy reports a `policy_hash` mismatch.
+/// Assumptions are not re-checked, so `assumptions_hash` is not compared.
+/// Derivation is the guest's own (`evaluate`): JSON that does not parse
+/// re-derives as `Verdict::Malformed` rather than being rejected.
+pub fn audit_output(committed: &GuestOutput, analysis_json: &str) -> Result<Vec<AuditMismatch>> {
+    let input = GuestInput {
+        schema_version: JOURNAL_SCHEMA_VERSION,
+        analysis_json: analysis_json.to_string(),
+        thinking_hash: committed.thinking_hash.clone(),
+        card_hash: committed.card_hash.clone(),
+        values_hash: committed.values_hash.clone(),
+        model: committed.model.clone(),
+        policy: Default::default(),
+        hash_algo: committed.hash_algo,
+        max_evidence_length: committed.max_evidence_length,
+        assumptions: Vec::new(),
     };
+    let derived = evaluate(&input).map_err(ProverError::InvalidAnalysis)?;
 
-    let env = ExecutorEnv::builder()
-        .write(&input)
-        .context("Failed to write input to executor env")?
-        .build()
-        .context("Failed to build executor env")?;
+    let fields = [
+        ("verdict", committed.verdict.to_string(), derived.verdict.to_string()),
+        ("action", committed.action.to_string(), derived.action.to_string()),
+        ("confidence_bps", committed.confidence_bps.to_string(), derived.confidence_bps.to_string()),
+        ("concerns_hash", committed.concerns_hash.clone(), derived.concerns_hash),
+        ("policy_hash", committed.policy_hash.clone(), derived.policy_hash),
+        (
+            "stated_verdict_matches",
+            committed.stated_verdict_matches.to_string(),
+            derived.stated_verdict_matches.to_string(),
+        ),
+    ];
+    Ok(fields
+        .into_iter()
+        .filter(|(_, committed, derived)| committed != derived)
+        .map(|(field, committed, derived)| AuditMismatch { field, committed, derived })
+        .collect())
+}
 
-    let prover = default_prover();
-    let prove_info = prover
-        .prove(env, AIP_ZKVM_GUEST_ELF)
-        .context("Failed to generate proof")?;
+/// Prove verdict derivation for several inputs in one receipt, using the
+/// batch guest. The fixed proving overhead is paid once for the whole batch.
+///
+/// Returns one output per input, in input order; each equals what
+/// `prove_from_input` would have committed for that input alone. Batches
+/// must hold 1 to MAX_BATCH_SIZE inputs, and every analysis is checked with
+/// `check_analysis` before proving starts. Verify the receipt with
+/// `verify_batch_proof`.
+pub fn prove_batch(inputs: &[GuestInput]) -> Result<(Receipt, Vec<GuestOutput>)> {
+    prove_batch_with_backend(ProverBackend::from_env(), inputs)
+}
 
-    let receipt = prove_info.receipt;
-    let output: GuestOutput = receipt
-        .journal
-        .decode()
-        .context("Failed to decode journal output")?;
+/// `prove_batch` on an explicitly chosen backend.
+pub fn prove_batch_with_backend(
+    backend: ProverBackend,
+    inputs: &[GuestInput],
+) -> Result<(Receipt, Vec<GuestOutput>)> {
+    let batch = check_batch(inputs)?;
+    let (receipt, _) = run_guest(backend, Guest::Batch, &batch, Vec::new())?;
+    let outputs = tracing::info_span!("decode_journal").in_scope(|| decode_batch_output(&receipt))?;
+    Ok((receipt, outputs))
+}
+
+/// Check a batch's size and every analysis in it before proving.
+fn check_batch(inputs: &[GuestInput]) -> Result<GuestInputBatch> {
+    if inputs.is_empty() || inputs.len() > MAX_BATCH_SIZE {
+        return Err(ProverError::BatchSize {
+            count: inputs.len(),
+            max: MAX_BATCH_SIZE,
+        });
+    }
+    for (index, input) in inputs.iter().enumerate() {
+        check_analysis(&input.analysis_json)
+            .and_then(|_| check_commitments(input))
+            .and_then(|_| check_assumptions(input, &[]))
+            .map_err(|e| ProverError::BatchInput {
+                index,
+                source: Box::new(e),
+            })?;
+    }
+    Ok(GuestInputBatch { inputs: inputs.to_vec() })
+}
+
+/// Parse and validate the analysis JSON exactly as the guest will parse it.
+///
+/// JSON that fails validation or exceeds `MAX_ANALYSIS_JSON_LEN` bytes makes
+/// the guest abort, which only surfaces after seconds of proving as an opaque
+/// error; this reports the problem up front. JSON that does not parse is an
+/// error here too, although the guest would commit it as
+/// `Verdict::Malformed`; see `check_analysis_allowing_malformed`.
+pub fn check_analysis(analysis_json: &str) -> Result<AnalysisResponse> {
+    if analysis_json.len() > aip_zkvm_core::MAX_ANALYSIS_JSON_LEN {
+        return Err(ProverError::InvalidAnalysis(aip_zkvm_core::ValidationError::AnalysisTooLarge {
+            len: analysis_json.len(),
+        }));
+    }
+    let response: AnalysisResponse =
+        serde_json::from_str(extract_json(analysis_json)).map_err(ProverError::ParseAnalysis)?;
+    response.validate().map_err(ProverError::InvalidAnalysis)?;
+    Ok(response)
+}
 
+/// `check_analysis`, except that JSON which does not parse passes: the guest
+/// proves it as `Verdict::Malformed`.
+pub fn check_analysis_allowing_malformed(analysis_json: &str) -> Result<()> {
+    match check_analysis(analysis_json) {
+        Ok(_) | Err(ProverError::ParseAnalysis(_)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+fn prove_input_with_stats(
+    backend: ProverBackend,
+    input: &GuestInput,
+) -> Result<(Receipt, GuestOutput, ProveStats)> {
+    check_analysis(&input.analysis_json)?;
+    check_commitments(input)?;
+    execute_and_prove(backend, input, Vec::new())
+}
+
+/// Prove verdict derivation composed with upstream proofs.
+///
+/// Each receipt is added to the executor as an assumption, and
+/// `input.assumptions` is replaced with their image IDs and journals, which
+/// the guest checks with `env::verify`. The verdict proof then holds only if
+/// every upstream proof does, without re-proving their claims, and commits
+/// `hash_assumptions` of them as `assumptions_hash`.
+pub fn prove_with_assumptions(input: &GuestInput, assumptions: Vec<Receipt>) -> Result<(Receipt, GuestOutput)> {
+    let mut input = input.clone();
+    input.assumptions = assumptions.iter().map(assumption_from_receipt).collect::<Result<_>>()?;
+    check_analysis(&input.analysis_json)?;
+    check_commitments(&input)?;
+    let (receipt, output, _) = execute_and_prove(ProverBackend::from_env(), &input, assumptions)?;
     Ok((receipt, output))
 }
 
-/// Verify a STARK receipt and extract the guest output.
+/// The `Assumption` recording `receipt`: the image ID it was proven under
+/// and its journal. Does not verify the receipt.
+pub fn assumption_from_receipt(receipt: &Receipt) -> Result<Assumption> {
+    let claim = receipt
+        .claim()
+        .map_err(crate::error::BoxError::from)
+        .and_then(|claim| Ok(claim.value()?))
+        .map_err(|source| ProverError::Deserialize {
+            what: "receipt claim",
+            source,
+        })?;
+    let image_id = risc0_zkvm::sha::Digestible::digest(&claim.pre);
+    Ok(Assumption {
+        image_id: image_id.into(),
+        journal: receipt.journal.bytes.clone(),
+    })
+}
+
+/// Check `input.assumptions` records exactly the `receipts` that will be
+/// added to the executor, in order; the guest would otherwise commit claims
+/// nothing backs.
+fn check_assumptions(input: &GuestInput, receipts: &[Receipt]) -> Result<()> {
+    let matches = input.assumptions.len() == receipts.len()
+        && input
+            .assumptions
+            .iter()
+            .zip(receipts)
+            .all(|(assumption, receipt)| assumption_from_receipt(receipt).is_ok_and(|r| r == *assumption));
+    if !matches {
+        return Err(ProverError::AssumptionMismatch {
+            listed: input.assumptions.len(),
+            receipts: receipts.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Check the pass-through hashes with `validate_hash_hex`, so a typo is
+/// reported instead of committed to the journal.
+pub fn check_commitments(input: &GuestInput) -> Result<()> {
+    for (field, hash) in [
+        ("thinking_hash", &input.thinking_hash),
+        ("card_hash", &input.card_hash),
+        ("values_hash", &input.values_hash),
+    ] {
+        validate_hash_hex(hash).map_err(|source| ProverError::InvalidCommitment { field, source })?;
+    }
+    Ok(())
+}
+
+/// Run the guest and prove it, with no pre-checks on the input beyond its
+/// assumptions matching `assumptions`.
+fn execute_and_prove(
+    backend: ProverBackend,
+    input: &GuestInput,
+    assumptions: Vec<Receipt>,
+) -> Result<(Receipt, GuestOutput, ProveStats)> {
+    check_assumptions(input, &assumptions)?;
+    let (receipt, stats) = run_guest(backend, Guest::Single, input, assumptions)?;
+    let output = tracing::info_span!("decode_journal").in_scope(|| decode_output(&receipt))?;
+
+    Ok((receipt, output, stats))
+}
+
+/// A guest program embedded in this build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Guest {
+    Single,
+    Batch,
+}
+
+impl Guest {
+    fn elf(self) -> &'static [u8] {
+        match self {
+            Guest::Single => AIP_ZKVM_GUEST_ELF,
+            Guest::Batch => AIP_ZKVM_GUEST_BATCH_ELF,
+        }
+    }
+
+    /// The guest's initial memory image, built on first use and cached.
+    ///
+    /// Loading the ELF and hashing its pages is the only per-proof setup
+    /// that doesn't depend on the input. The image is never mutated, so one
+    /// copy per process is safe; each proof gets a clone to execute on. A
+    /// load failure is cached too, since the ELF is compiled in.
+    fn image(self) -> Result<MemoryImage> {
+        type Cached = OnceLock<std::result::Result<MemoryImage, String>>;
+        static SINGLE: Cached = OnceLock::new();
+        static BATCH: Cached = OnceLock::new();
+
+        let cached = match self {
+            Guest::Single => &SINGLE,
+            Guest::Batch => &BATCH,
+        };
+        cached
+            .get_or_init(|| {
+                let program = Program::load_elf(self.elf(), GUEST_MAX_MEM as u32)
+                    .map_err(|e| format!("guest ELF does not load: {e}"))?;
+                MemoryImage::new(&program, PAGE_SIZE as u32)
+                    .map_err(|e| format!("guest memory image does not build: {e}"))
+            })
+            .clone()
+            .map_err(ProverError::GuestUnavailable)
+    }
+}
+
+/// Prove `guest` on `input`, returning the receipt undecoded.
+///
+/// What is reused across proofs, and what isn't:
+/// - the ELF is compiled in, so it is never re-read;
+/// - the memory image is cached by `Guest::image` for local proving;
+/// - the `ExecutorEnv` carries this proof's input and is consumed by the
+///   executor, so it is built per proof;
+/// - the prover handle is an `Rc` (not `Send`, so it can't be shared with
+///   the server's blocking pool) and holds only options, so building one per
+///   proof costs nothing measurable.
+///
+/// Remote and IPC provers take the ELF and load it themselves.
+fn run_guest(
+    backend: ProverBackend,
+    guest: Guest,
+    input: &impl serde::Serialize,
+    assumptions: Vec<Receipt>,
+) -> Result<(Receipt, ProveStats)> {
+    let env = tracing::info_span!("build_env").in_scope(|| {
+        let mut builder = ExecutorEnv::builder();
+        for receipt in assumptions {
+            builder.add_assumption(receipt);
+        }
+        builder
+            .write(input)
+            .and_then(|builder| builder.build())
+            .map_err(|e| ProverError::Serialize {
+                what: "guest input to executor env",
+                source: e.into(),
+            })
+    })?;
+
+    let prove_info = tracing::info_span!("prove", ?backend).in_scope(|| {
+        let prover = backend.prover();
+        let proved = if prover.get_name() == "local" {
+            prove_local(env, guest.image()?)
+        } else {
+            prover.prove(env, guest.elf())
+        };
+        proved.map_err(|e| ProverError::Prove {
+            backend,
+            source: e.into(),
+        })
+    })?;
+
+    let stats = ProveStats {
+        user_cycles: prove_info.stats.user_cycles,
+        total_cycles: prove_info.stats.total_cycles,
+        segments: prove_info.stats.segments,
+    };
+
+    Ok((prove_info.receipt, stats))
+}
+
+/// `LocalProver::prove`, but executing from an already-built memory image.
+fn prove_local(env: ExecutorEnv<'_>, image: MemoryImage) -> risc0_zkvm::Result<ProveInfo> {
+    let session = ExecutorImpl::new(env, image)?.run()?;
+    get_prover_server(&ProverOpts::default())?.prove_session(&VerifierContext::default(), &session)
+}
+
+/// Check this process can prove: the embedded guest ELF loads and hashes to
+/// the embedded image ID, and a prover for `ProverBackend::from_env` can be
+/// constructed.
+///
+/// The ELF check is CPU-bound (it builds the guest's memory image), but the
+/// image is cached by `Guest::image`, so only the first call pays for it and
+/// the first proof then starts warm.
+pub fn check_proving_ready() -> Result<()> {
+    let image_id = Guest::Single.image()?.compute_id();
+    if image_id != risc0_zkvm::sha::Digest::from(aip_zkvm_methods::AIP_ZKVM_GUEST_ID) {
+        return Err(ProverError::GuestUnavailable(format!(
+            "guest ELF has image ID {image_id}, expected {}",
+            guest_image_id_hex()
+        )));
+    }
+
+    ProverBackend::from_env().prover();
+    Ok(())
+}
+
+/// Compress a receipt into a Groth16 SNARK for long-term storage.
+///
+/// Runs the recursion (identity-p254) and Groth16 wrapping pipeline, taking a
+/// composite STARK receipt of hundreds of KB down to a few hundred bytes of
+/// seal. The journal is unchanged. Compressing an already-Groth16 receipt is a
+/// no-op. Groth16 wrapping is only supported on x86_64 Linux with Docker.
+pub fn compress_receipt(receipt: &Receipt) -> Result<Receipt> {
+    default_prover()
+        .compress(&ProverOpts::groth16(), receipt)
+        .map_err(|e| ProverError::Compress(e.into()))
+}
+
+/// Verify a receipt and extract the guest output.
 ///
-/// Verifies both the STARK proof integrity and the image ID match.
+/// Verifies both the proof integrity and the image ID match against the
+/// built-in guest (`AIP_ZKVM_GUEST_ID`). Accepts composite, succinct and
+/// Groth16 receipts alike. Dev-mode (fake) receipts are rejected with an
+/// explicit error unless dev mode is active.
+///
+/// The output's `policy_hash` pins the verdict rules; compare it with
+/// `VerdictPolicy::policy_hash` of the policy you expect. Its
+/// `stated_verdict_matches` is `false` when the model's own verdict differs
+/// from the derived one, which is worth flagging even on a valid proof.
 pub fn verify_verdict_proof(receipt: &Receipt) -> Result<GuestOutput> {
+    verify_verdict_proof_with_image_id(receipt, aip_zkvm_methods::AIP_ZKVM_GUEST_ID)
+}
+
+/// Kind and encoded size of a receipt, for seeing where bytes go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptInfo {
+    /// `composite`, `succinct`, `groth16` or `fake`.
+    pub kind: String,
+    /// Length of the uncompressed `receipt_to_bytes` encoding.
+    pub byte_size: usize,
+}
+
+/// Report a receipt's kind and serialized size without verifying it.
+pub fn receipt_info(receipt: &Receipt) -> Result<ReceiptInfo> {
+    let kind = match &receipt.inner {
+        InnerReceipt::Composite(_) => "composite",
+        InnerReceipt::Succinct(_) => "succinct",
+        InnerReceipt::Groth16(_) => "groth16",
+        InnerReceipt::Fake(_) => "fake",
+        _ => "unknown",
+    };
+    Ok(ReceiptInfo {
+        kind: kind.to_string(),
+        byte_size: receipt_to_bytes(receipt)?.len(),
+    })
+}
+
+/// Like `verify_verdict_proof`, but also returns the receipt's `ReceiptInfo`.
+pub fn verify_verdict_proof_verbose(receipt: &Receipt) -> Result<(GuestOutput, ReceiptInfo)> {
+    let output = verify_verdict_proof(receipt)?;
+    Ok((output, receipt_info(receipt)?))
+}
+
+/// Verify a receipt against an explicit guest image ID.
+///
+/// Use this for receipts produced by an older (or newer) guest ELF than the
+/// one embedded in this build.
+pub fn verify_verdict_proof_with_image_id(
+    receipt: &Receipt,
+    image_id: [u32; 8],
+) -> Result<GuestOutput> {
+    if is_fake_receipt(receipt) && !is_dev_mode() {
+        return Err(ProverError::DevModeReceipt);
+    }
+
     // Verify the receipt against the expected image ID
     receipt
-        .verify(aip_zkvm_methods::AIP_ZKVM_GUEST_ID)
-        .context("Receipt verification failed")?;
+        .verify(image_id)
+        .map_err(|e| ProverError::Verify(e.into()))?;
 
-    let output: GuestOutput = receipt
+    decode_output(receipt)
+}
+
+/// Verify many receipts against the embedded guest, returning one result per
+/// receipt in input order.
+///
+/// With the `parallel` feature the receipts are verified across rayon's
+/// thread pool; verification is CPU-bound and independent per receipt, so
+/// this scales with cores. Without it they are verified one after another.
+pub fn verify_batch(receipts: &[Receipt]) -> Vec<Result<GuestOutput>> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        receipts.par_iter().map(verify_verdict_proof).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        receipts.iter().map(verify_verdict_proof).collect()
+    }
+}
+
+/// Decode the committed guest output from a receipt's journal.
+///
+/// This performs NO proof verification — only use it on receipts that have
+/// already been verified (or are otherwise trusted). `verify_verdict_proof`
+/// remains the trust-establishing path.
+pub fn decode_output(receipt: &Receipt) -> Result<GuestOutput> {
+    check_journal_schema_version(receipt)?;
+    receipt
+        .journal
+        .decode()
+        .map_err(|e| ProverError::DecodeJournal(e.into()))
+}
+
+/// Verify a batch receipt from `prove_batch` against the embedded batch
+/// guest and return its outputs, in input order.
+pub fn verify_batch_proof(receipt: &Receipt) -> Result<Vec<GuestOutput>> {
+    if is_fake_receipt(receipt) && !is_dev_mode() {
+        return Err(ProverError::DevModeReceipt);
+    }
+    receipt
+        .verify(aip_zkvm_methods::AIP_ZKVM_GUEST_BATCH_ID)
+        .map_err(|e| ProverError::Verify(e.into()))?;
+    decode_batch_output(receipt)
+}
+
+/// Decode the outputs committed by the batch guest. Like `decode_output`,
+/// this performs NO proof verification.
+pub fn decode_batch_output(receipt: &Receipt) -> Result<Vec<GuestOutput>> {
+    check_journal_schema_version(receipt)?;
+    let batch: GuestOutputBatch = receipt
         .journal
         .decode()
-        .context("Failed to decode journal output")?;
+        .map_err(|e| ProverError::DecodeJournal(e.into()))?;
+    Ok(batch.outputs)
+}
+
+/// Reject journals committed under a layout this build can't decode.
+fn check_journal_schema_version(receipt: &Receipt) -> Result<()> {
+    let found = journal_schema_version(&receipt.journal.bytes)
+        .ok_or_else(|| ProverError::DecodeJournal("journal is shorter than one word".into()))?;
+    if found != JOURNAL_SCHEMA_VERSION {
+        return Err(ProverError::UnsupportedSchemaVersion {
+            found,
+            expected: JOURNAL_SCHEMA_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// Hex-encoded image ID of the embedded guest program.
+///
+/// Words are emitted little-endian, matching the format stored in the
+/// `verdict_proofs.image_id` column and accepted by the WASM verifier.
+pub fn guest_image_id_hex() -> String {
+    image_id_to_hex(&aip_zkvm_methods::AIP_ZKVM_GUEST_ID)
+}
+
+/// Hex-encoded image ID of the embedded batch guest, in the same format as
+/// `guest_image_id_hex`.
+pub fn guest_batch_image_id_hex() -> String {
+    image_id_to_hex(&aip_zkvm_methods::AIP_ZKVM_GUEST_BATCH_ID)
+}
+
+/// Image ID of the embedded guest program as 32 bytes (words little-endian).
+pub fn guest_image_id_bytes() -> [u8; 32] {
+    image_id_to_bytes(&aip_zkvm_methods::AIP_ZKVM_GUEST_ID)
+}
+
+/// Serialize an image ID to 32 bytes, each word little-endian. This is the
+/// byte order used everywhere an image ID leaves the process.
+pub fn image_id_to_bytes(image_id: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(image_id) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// Hex form of `image_id_to_bytes`; the inverse of `image_id_from_hex`.
+pub fn image_id_to_hex(image_id: &[u32; 8]) -> String {
+    hex::encode(image_id_to_bytes(image_id))
+}
+
+/// Parse a 64-char hex image ID (as produced by `guest_image_id_hex`) into
+/// the `[u32; 8]` form expected by `Receipt::verify`.
+pub fn image_id_from_hex(image_id_hex: &str) -> Result<[u32; 8]> {
+    let bytes = hex::decode(image_id_hex.trim())
+        .map_err(|e| ProverError::InvalidImageId(format!("not valid hex ({})", e)))?;
+    let bytes: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| ProverError::InvalidImageId(format!("must be 32 bytes, got {}", bytes.len())))?;
+    Ok(image_id_from_bytes(&bytes))
+}
+
+/// Inverse of `image_id_to_bytes`.
+pub fn image_id_from_bytes(bytes: &[u8; 32]) -> [u32; 8] {
+    let mut id = [0u32; 8];
+    for (word, chunk) in id.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    id
+}
+
+/// SHA-256 of the embedded guest ELF binary, hex-encoded.
+///
+/// The image ID is derived from the ELF, but pinning the raw artifact hash
+/// lets operators tie a proof to the exact build that produced it.
+pub fn guest_elf_sha256() -> String {
+    hex::encode(Sha256::digest(AIP_ZKVM_GUEST_ELF))
+}
+
+/// Check the embedded guest ELF against a pinned SHA-256 hex digest.
+pub fn check_pinned_elf(expected_sha256: &str) -> Result<()> {
+    let actual = guest_elf_sha256();
+    if !actual.eq_ignore_ascii_case(expected_sha256.trim()) {
+        return Err(ProverError::ElfMismatch {
+            expected: expected_sha256.trim().to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
 
-    Ok(output)
+/// Whether a receipt is a dev-mode fake with no cryptographic seal.
+pub fn is_fake_receipt(receipt: &Receipt) -> bool {
+    matches!(receipt.inner, InnerReceipt::Fake(_))
 }
 
 /// Serialize a receipt to bytes for transport/storage.
+///
+/// The receipt kind is part of the encoding, so compressed receipts from
+/// `compress_receipt` round-trip through `receipt_from_bytes` unchanged.
+///
+/// This is the canonical encoding, and the only one we emit: bincode 1.x's
+/// `bincode::serialize` defaults, i.e. little-endian, fixed-width integers
+/// and `u64` length prefixes.
 pub fn receipt_to_bytes(receipt: &Receipt) -> Result<Vec<u8>> {
-    bincode::serialize(receipt).context("Failed to serialize receipt")
+    bincode::serialize(receipt).map_err(|e| ProverError::Serialize {
+        what: "receipt",
+        source: e.into(),
+    })
 }
 
-/// Deserialize a receipt from bytes.
+/// Deserialize a receipt from bytes in the canonical `receipt_to_bytes`
+/// encoding.
 pub fn receipt_from_bytes(bytes: &[u8]) -> Result<Receipt> {
-    bincode::deserialize(bytes).context("Failed to deserialize receipt")
+    bincode::deserialize(bytes).map_err(|e| ProverError::Deserialize {
+        what: "receipt",
+        source: e.into(),
+    })
+}
+
+/// Deserialize a receipt encoded with an explicit bincode configuration.
+pub fn receipt_from_bytes_with<O: bincode::Options>(bytes: &[u8], options: O) -> Result<Receipt> {
+    options.deserialize(bytes).map_err(|e| ProverError::Deserialize {
+        what: "receipt",
+        source: e.into(),
+    })
+}
+
+/// Deserialize a receipt that another producer may have encoded with a
+/// different bincode configuration.
+///
+/// Tries the canonical encoding first, then fixed-width big-endian,
+/// variable-width little-endian and variable-width big-endian, returning the
+/// first that decodes. The alternatives reject trailing bytes, so a
+/// misdetected configuration is unlikely to decode. On failure the error is
+/// the canonical encoding's. Decoding proves nothing: verify the receipt as
+/// usual.
+pub fn receipt_from_bytes_compat(bytes: &[u8]) -> Result<Receipt> {
+    use bincode::Options;
+    receipt_from_bytes(bytes).or_else(|canonical| {
+        let options = bincode::DefaultOptions::new;
+        receipt_from_bytes_with(bytes, options().with_fixint_encoding().with_big_endian())
+            .or_else(|_| receipt_from_bytes_with(bytes, options()))
+            .or_else(|_| receipt_from_bytes_with(bytes, options().with_big_endian()))
+            .map_err(|_| canonical)
+    })
+}
+
+/// Leading byte of a zstd-compressed receipt from `receipt_to_bytes_compressed`.
+///
+/// Plain `receipt_to_bytes` output starts with the bincode index of the
+/// receipt kind (a small integer), so it never begins with this tag and both
+/// forms can be told apart without any other framing.
+pub const COMPRESSED_RECEIPT_TAG: u8 = 0xA1;
+
+#[cfg(feature = "compression")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// Largest receipt accepted from a caller, in bytes. `decompress_receipt_bytes`
+/// stops once its output passes this, so a small compressed payload can't
+/// expand without bound.
+pub const MAX_RECEIPT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Serialize a receipt and zstd-compress it, prefixed with
+/// `COMPRESSED_RECEIPT_TAG`. Read back with `receipt_from_bytes_compressed`.
+#[cfg(feature = "compression")]
+pub fn receipt_to_bytes_compressed(receipt: &Receipt) -> Result<Vec<u8>> {
+    let raw = receipt_to_bytes(receipt)?;
+    let compressed = zstd::bulk::compress(&raw, ZSTD_LEVEL).map_err(|e| ProverError::Serialize {
+        what: "compressed receipt",
+        source: e.into(),
+    })?;
+    let mut out = Vec::with_capacity(1 + compressed.len());
+    out.push(COMPRESSED_RECEIPT_TAG);
+    out.extend_from_slice(&compressed);
+    Ok(out)
+}
+
+/// Deserialize a receipt written by either `receipt_to_bytes` or
+/// `receipt_to_bytes_compressed`. Uncompressed receipts in another bincode
+/// configuration are accepted too; see `receipt_from_bytes_compat`.
+pub fn receipt_from_bytes_compressed(bytes: &[u8]) -> Result<Receipt> {
+    receipt_from_bytes_compat(&decompress_receipt_bytes(bytes)?)
+}
+
+/// Undo `receipt_to_bytes_compressed`, yielding plain `receipt_to_bytes`
+/// output. Uncompressed input is returned as is. Decompressed output over
+/// MAX_RECEIPT_BYTES is an error, and decompression stops as soon as it is
+/// exceeded.
+pub fn decompress_receipt_bytes(bytes: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>> {
+    match bytes.split_first() {
+        #[cfg(feature = "compression")]
+        Some((&COMPRESSED_RECEIPT_TAG, payload)) => {
+            use std::io::Read;
+            let error = |source: crate::error::BoxError| ProverError::Deserialize {
+                what: "compressed receipt",
+                source,
+            };
+            let mut out = Vec::new();
+            zstd::stream::Decoder::new(payload)
+                .and_then(|decoder| decoder.take(MAX_RECEIPT_BYTES as u64 + 1).read_to_end(&mut out))
+                .map_err(|e| error(e.into()))?;
+            if out.len() > MAX_RECEIPT_BYTES {
+                return Err(error(
+                    format!("decompresses to more than the {} byte limit", MAX_RECEIPT_BYTES).into(),
+                ));
+            }
+            Ok(std::borrow::Cow::Owned(out))
+        }
+        #[cfg(not(feature = "compression"))]
+        Some((&COMPRESSED_RECEIPT_TAG, _)) => {
+            Err(ProverError::Deserialize {
+                what: "compressed receipt",
+                source: "zstd support requires the `compression` feature".into(),
+            })
+        }
+        _ => Ok(std::borrow::Cow::Borrowed(bytes)),
+    }
 }
 
 #[cfg(test)]
@@ -80,25 +1100,313 @@ mod tests {
     const BOUNDARY_INJECTION_JSON: &str = include_str!("../../tests/fixtures/boundary_injection.json");
     const BOUNDARY_DECEPTION_JSON: &str = include_str!("../../tests/fixtures/boundary_deception.json");
 
+    const THINKING_HASH: &str = "a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2";
+    const CARD_HASH: &str = "b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3";
+    const VALUES_HASH: &str = "c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4";
+
+    #[test]
+    fn test_image_id_to_bytes_is_little_endian() {
+        let id = [0x04030201, 0, 0, 0, 0, 0, 0, 0x201f1e1d];
+        let bytes = image_id_to_bytes(&id);
+        assert_eq!(bytes[..4], [1, 2, 3, 4]);
+        assert_eq!(bytes[28..], [0x1d, 0x1e, 0x1f, 0x20]);
+        assert_eq!(image_id_from_hex(&image_id_to_hex(&id)).unwrap(), id);
+    }
+
+    #[test]
+    fn test_image_id_hex_roundtrip() {
+        let id = image_id_from_hex(&guest_image_id_hex()).expect("valid image ID");
+        assert_eq!(id, aip_zkvm_methods::AIP_ZKVM_GUEST_ID);
+        assert_eq!(hex::encode(guest_image_id_bytes()), guest_image_id_hex());
+        assert!(image_id_from_hex("abcd").is_err());
+        assert!(image_id_from_hex(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_check_analysis_fixtures() {
+        for json in [CLEAR_JSON, REVIEW_JSON, BOUNDARY_INJECTION_JSON, BOUNDARY_DECEPTION_JSON] {
+            check_analysis(json).expect("fixture should validate");
+        }
+    }
+
+    #[test]
+    fn test_audit_output_reports_mismatches() {
+        let committed = derive_output(REVIEW_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model").unwrap();
+        assert_eq!(audit_output(&committed, REVIEW_JSON).unwrap(), vec![]);
+
+        let mismatches = audit_output(&committed, CLEAR_JSON).unwrap();
+        let fields: Vec<_> = mismatches.iter().map(|m| m.field).collect();
+        assert!(fields.contains(&"verdict") && fields.contains(&"concerns_hash"), "{:?}", mismatches);
+        let verdict = mismatches.iter().find(|m| m.field == "verdict").unwrap();
+        assert_eq!(verdict.committed, "review_needed");
+        assert_eq!(verdict.derived, "clear");
+
+        let malformed = audit_output(&committed, "not json").unwrap();
+        assert_eq!(malformed[0].derived, "malformed");
+    }
+
+    #[test]
+    fn test_prove_rejects_invalid_commitment_before_proving() {
+        let err = prove_verdict_derivation(CLEAR_JSON, "", "not-a-hash", "", "m").unwrap_err();
+        assert!(
+            matches!(err, ProverError::InvalidCommitment { field: "card_hash", .. }),
+            "{:?}",
+            err
+        );
+
+        let mut input = guest_input(CLEAR_JSON, "", "", "", "m");
+        input.values_hash = "ab".repeat(31);
+        let err = prove_batch(&[input]).unwrap_err();
+        assert!(matches!(err, ProverError::BatchInput { index: 0, .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn test_prove_rejects_invalid_analysis_before_proving() {
+        let bad_confidence = CLEAR_JSON.replace("\"confidence\": 0.95", "\"confidence\": 7.0");
+        assert_ne!(bad_confidence, CLEAR_JSON);
+        let err = prove_verdict_derivation(&bad_confidence, "a", "b", "c", "m").unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ProverError::InvalidAnalysis(aip_zkvm_core::ValidationError::ConfidenceOutOfRange(_))
+            ),
+            "{:?}",
+            err
+        );
+
+        let err = prove_verdict_derivation("not json", "a", "b", "c", "m").unwrap_err();
+        assert!(matches!(err, ProverError::ParseAnalysis(_)), "{:?}", err);
+        assert!(err.to_string().contains("does not parse"), "{}", err);
+    }
+
+    #[test]
+    fn test_derive_output_fixtures() {
+        let cases = [
+            (CLEAR_JSON, aip_zkvm_core::Verdict::Clear, aip_zkvm_core::Action::Continue),
+            (REVIEW_JSON, aip_zkvm_core::Verdict::ReviewNeeded, aip_zkvm_core::Action::LogAndContinue),
+            (BOUNDARY_INJECTION_JSON, aip_zkvm_core::Verdict::BoundaryViolation, aip_zkvm_core::Action::DenyAndEscalate),
+        ];
+        for (json, verdict, action) in cases {
+            let output = derive_output(json, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model").unwrap();
+            assert_eq!(output.verdict, verdict);
+            assert_eq!(output.action, action);
+            assert_eq!(output.concerns_hash, aip_zkvm_core::hash_concerns(&check_analysis(json).unwrap().concerns));
+            assert_eq!(output.max_evidence_length, MAX_EVIDENCE_LENGTH as u32);
+            assert_eq!(output.model, "test-model");
+        }
+        assert_eq!(derive_output(CLEAR_JSON, "", "", "", "m").unwrap().confidence_bps, 9_500);
+
+        // A longer evidence limit keeps evidence the default would cut.
+        let long_evidence = format!(
+            r#"{{"verdict": "review_needed", "concerns": [{{"category": "value_misalignment", "severity": "medium", "description": "d", "evidence": "{}"}}], "confidence": 0.5, "reasoning_summary": "s"}}"#,
+            "x".repeat(500)
+        );
+        let mut input = guest_input(&long_evidence, "", "", "", "m");
+        let default_hash = derive_from_input(&input).unwrap().concerns_hash;
+        input.max_evidence_length = 1_000;
+        let output = derive_from_input(&input).unwrap();
+        assert_eq!(output.max_evidence_length, 1_000);
+        assert_eq!(
+            output.concerns_hash,
+            aip_zkvm_core::hash_concerns_with_max_evidence(&check_analysis(&long_evidence).unwrap().concerns, 1_000)
+        );
+        assert_ne!(output.concerns_hash, default_hash);
+
+        let err = derive_output("not json", "a", "b", "c", "m").unwrap_err();
+        assert!(matches!(err, ProverError::ParseAnalysis(_)), "{:?}", err);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_derive_output_matches_proof() {
+        for json in [CLEAR_JSON, REVIEW_JSON, BOUNDARY_INJECTION_JSON, BOUNDARY_DECEPTION_JSON] {
+            let (_, proven) = prove_verdict_derivation(json, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model")
+                .expect("Proving failed");
+            let derived = derive_output(json, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model").unwrap();
+            assert_eq!(derived, proven);
+        }
+    }
+
+    #[test]
+    fn test_prove_batch_rejects_bad_batches_before_proving() {
+        let err = prove_batch(&[]).unwrap_err();
+        assert!(matches!(err, ProverError::BatchSize { count: 0, .. }), "{:?}", err);
+
+        let input = guest_input(CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "m");
+        let err = prove_batch(&vec![input.clone(); MAX_BATCH_SIZE + 1]).unwrap_err();
+        assert!(matches!(err, ProverError::BatchSize { .. }), "{:?}", err);
+
+        let inputs = [input, guest_input("not json", THINKING_HASH, CARD_HASH, VALUES_HASH, "m")];
+        let err = prove_batch(&inputs).unwrap_err();
+        match err {
+            ProverError::BatchInput { index: 1, source } => {
+                assert!(matches!(*source, ProverError::ParseAnalysis(_)), "{:?}", source)
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_batch_output() {
+        let outputs = vec![sample_output(), GuestOutput { model: "other".to_string(), ..sample_output() }];
+        let batch = GuestOutputBatch {
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            outputs: outputs.clone(),
+        };
+        let receipt = fake_receipt(&batch, aip_zkvm_methods::AIP_ZKVM_GUEST_BATCH_ID).unwrap();
+        assert_eq!(decode_batch_output(&receipt).unwrap(), outputs);
+
+        let stale = GuestOutputBatch { schema_version: JOURNAL_SCHEMA_VERSION - 1, ..batch };
+        let receipt = fake_receipt(&stale, aip_zkvm_methods::AIP_ZKVM_GUEST_BATCH_ID).unwrap();
+        assert!(matches!(
+            decode_batch_output(&receipt),
+            Err(ProverError::UnsupportedSchemaVersion { .. })
+        ));
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_batch_matches_single_proofs() {
+        let inputs: Vec<GuestInput> = [CLEAR_JSON, REVIEW_JSON, BOUNDARY_INJECTION_JSON]
+            .iter()
+            .map(|json| guest_input(json, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model"))
+            .collect();
+        let (receipt, outputs) = prove_batch(&inputs).expect("Proving failed");
+
+        assert_eq!(verify_batch_proof(&receipt).expect("Verification failed"), outputs);
+        for (input, output) in inputs.iter().zip(&outputs) {
+            assert_eq!(output, &derive_from_input(input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_analysis_to_json_roundtrips() {
+        for json in [CLEAR_JSON, REVIEW_JSON, BOUNDARY_INJECTION_JSON, BOUNDARY_DECEPTION_JSON] {
+            let response = check_analysis(json).unwrap();
+            let encoded = analysis_to_json(&response).unwrap();
+            let reparsed = check_analysis(&encoded).expect("serialized response should validate");
+            assert_eq!(analysis_to_json(&reparsed).unwrap(), encoded);
+        }
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_analysis_matches_json_path() {
+        let response = check_analysis(REVIEW_JSON).unwrap();
+        let (_, from_struct) = prove_analysis(&response, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model")
+            .expect("Proving failed");
+        let (_, from_json) = prove_verdict_derivation(REVIEW_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model")
+            .expect("Proving failed");
+
+        assert_eq!(from_struct.verdict, from_json.verdict);
+        assert_eq!(from_struct.action, from_json.action);
+        assert_eq!(from_struct.concerns_hash, from_json.concerns_hash);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_malformed_analysis() {
+        let garbage = "I'm sorry, I can't produce JSON for that.";
+        let (receipt, output) = prove_verdict_derivation_allowing_malformed(
+            garbage, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Proving failed");
+
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::Malformed);
+        assert_eq!(output.action, aip_zkvm_core::Action::PauseForReview);
+        assert_eq!(output.concerns_hash, aip_zkvm_core::hash_raw_analysis(garbage));
+        assert_eq!(verify_verdict_proof(&receipt).expect("Verification failed").verdict, output.verdict);
+    }
+
+    #[test]
+    fn test_allowing_malformed_still_rejects_invalid_analysis() {
+        let bad_confidence = CLEAR_JSON.replace("\"confidence\": 0.95", "\"confidence\": 7.0");
+        let err = prove_verdict_derivation_allowing_malformed(&bad_confidence, "a", "b", "c", "m").unwrap_err();
+        assert!(matches!(err, ProverError::InvalidAnalysis(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_prove_rejects_oversized_analysis_before_proving() {
+        let oversized = format!("{}{}", CLEAR_JSON, " ".repeat(aip_zkvm_core::MAX_ANALYSIS_JSON_LEN));
+        for err in [
+            prove_verdict_derivation(&oversized, "a", "b", "c", "m").unwrap_err(),
+            prove_verdict_derivation_allowing_malformed(&oversized, "a", "b", "c", "m").unwrap_err(),
+        ] {
+            assert!(
+                matches!(
+                    err,
+                    ProverError::InvalidAnalysis(aip_zkvm_core::ValidationError::AnalysisTooLarge { .. })
+                ),
+                "{:?}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_prove_rejects_too_many_concerns_before_proving() {
+        let concern = r#"{"category": "value_misalignment", "severity": "low", "description": "d", "evidence": "e"}"#;
+        let analysis = |count: usize| {
+            format!(
+                r#"{{"verdict": "clear", "concerns": [{}], "confidence": 0.5, "reasoning_summary": "s"}}"#,
+                vec![concern; count].join(",")
+            )
+        };
+
+        check_analysis(&analysis(aip_zkvm_core::MAX_CONCERNS)).expect("limit is inclusive");
+        let err = prove_verdict_derivation(&analysis(aip_zkvm_core::MAX_CONCERNS + 1), "a", "b", "c", "m")
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ProverError::InvalidAnalysis(aip_zkvm_core::ValidationError::TooManyConcerns { .. })
+            ),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_check_proving_ready_requires_loadable_elf() {
+        // Builds without the RISC Zero toolchain embed an empty ELF.
+        if AIP_ZKVM_GUEST_ELF.is_empty() {
+            let err = check_proving_ready().unwrap_err();
+            assert!(matches!(err, ProverError::GuestUnavailable(_)), "{:?}", err);
+        } else {
+            check_proving_ready().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_guest_elf_sha256_stable() {
+        let hash = guest_elf_sha256();
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(hash, guest_elf_sha256());
+        assert!(check_pinned_elf(&hash).is_ok());
+        assert!(check_pinned_elf(&"0".repeat(64)).is_err());
+    }
+
     #[test]
     #[ignore] // Requires RISC Zero toolchain installed
     fn test_prove_clear() {
         let (receipt, output) = prove_verdict_derivation(
-            CLEAR_JSON, "abc123", "def456", "ghi789", "test-model",
+            CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
         ).expect("Proving failed");
 
         assert_eq!(output.verdict, aip_zkvm_core::Verdict::Clear);
         assert_eq!(output.action, aip_zkvm_core::Action::Continue);
+        assert_eq!(output.confidence_bps, 9_500);
 
         let verified = verify_verdict_proof(&receipt).expect("Verification failed");
         assert_eq!(verified.verdict, output.verdict);
+        assert_eq!(verified.confidence_bps, output.confidence_bps);
     }
 
     #[test]
     #[ignore] // Requires RISC Zero toolchain installed
     fn test_prove_boundary_injection() {
         let (receipt, output) = prove_verdict_derivation(
-            BOUNDARY_INJECTION_JSON, "abc123", "def456", "ghi789", "test-model",
+            BOUNDARY_INJECTION_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
         ).expect("Proving failed");
 
         assert_eq!(output.verdict, aip_zkvm_core::Verdict::BoundaryViolation);
@@ -108,11 +1416,346 @@ mod tests {
         assert_eq!(verified.verdict, output.verdict);
     }
 
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_review_needed() {
+        let (_, output) = prove_verdict_derivation(
+            REVIEW_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Proving failed");
+
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::ReviewNeeded);
+        assert_eq!(output.action, aip_zkvm_core::Action::LogAndContinue);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_boundary_deception() {
+        let (_, output) = prove_verdict_derivation(
+            BOUNDARY_DECEPTION_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Proving failed");
+
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::BoundaryViolation);
+        assert_eq!(output.action, aip_zkvm_core::Action::PauseForReview);
+    }
+
+    #[test]
+    #[ignore = "needs BONSAI_API_URL/BONSAI_API_KEY"]
+    fn test_prove_bonsai() {
+        assert_eq!(
+            ProverBackend::from_env(),
+            ProverBackend::Bonsai,
+            "BONSAI_API_URL and BONSAI_API_KEY must be set"
+        );
+
+        let (receipt, output) = prove_verdict_derivation_with_backend(
+            ProverBackend::Bonsai, CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Bonsai proving failed");
+
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::Clear);
+        let verified = verify_verdict_proof(&receipt).expect("Verification failed");
+        assert_eq!(verified.verdict, output.verdict);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_from_input() {
+        let input = GuestInput {
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            analysis_json: REVIEW_JSON.to_string(),
+            thinking_hash: THINKING_HASH.to_string(),
+            card_hash: CARD_HASH.to_string(),
+            values_hash: VALUES_HASH.to_string(),
+            model: "test-model".to_string(),
+            policy: Default::default(),
+            hash_algo: Default::default(),
+            max_evidence_length: aip_zkvm_core::MAX_EVIDENCE_LENGTH as u32,
+            assumptions: Vec::new(),
+        };
+        let (_, output) = prove_from_input(&input).expect("Proving failed");
+
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::ReviewNeeded);
+        assert_eq!(output.model, input.model);
+    }
+
+    #[test]
+    fn test_assumptions_must_match_receipts() {
+        let upstream = fake_receipt(&sample_output(), [3; 8]).unwrap();
+        let assumption = assumption_from_receipt(&upstream).unwrap();
+        assert_eq!(assumption.image_id, [3; 8]);
+        assert_eq!(assumption.journal, upstream.journal.bytes);
+
+        let mut input = guest_input(CLEAR_JSON, "", "", "", "m");
+        input.assumptions = vec![assumption];
+        check_assumptions(&input, std::slice::from_ref(&upstream)).unwrap();
+        assert!(check_assumptions(&input, &[fake_receipt(&sample_output(), [4; 8]).unwrap()]).is_err());
+
+        // Listed assumptions without their receipts are refused before proving.
+        let err = prove_from_input(&input).unwrap_err();
+        assert!(matches!(err, ProverError::AssumptionMismatch { listed: 1, receipts: 0 }), "{:?}", err);
+        let err = prove_batch(&[input]).unwrap_err();
+        assert!(matches!(err, ProverError::BatchInput { index: 0, .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn test_warm_up_proves_once() {
+        let mock = MockProver::default();
+        warm_up(&mock).unwrap();
+        assert_eq!(mock.proofs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_mock_prover_roundtrip() {
+        let prover = MockProver::default();
+        let (receipt, output, _) = prover
+            .prove(REVIEW_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model")
+            .unwrap();
+        assert_eq!(output, derive_output(REVIEW_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model").unwrap());
+        assert_eq!(prover.verify(&receipt, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap(), output);
+        assert!(prover.verify(&receipt, [1; 8]).is_err());
+        assert!(prover.prove(REVIEW_JSON, "zz", "", "", "m").is_err());
+        assert_eq!(prover.proofs.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_mock_prover_proves_unparseable_as_malformed() {
+        let garbage = "I'm sorry, I can't produce JSON for that.";
+        let (_, output, _) = MockProver::default().prove(garbage, "", "", "", "m").unwrap();
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::Malformed);
+        assert_eq!(output.concerns_hash, aip_zkvm_core::hash_raw_analysis(garbage));
+
+        let bad_confidence = CLEAR_JSON.replace("\"confidence\": 0.95", "\"confidence\": 7.0");
+        let err = MockProver::default().prove(&bad_confidence, "", "", "", "m").unwrap_err();
+        assert!(matches!(err, ProverError::InvalidAnalysis(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_receipt_info_reports_kind_and_size() {
+        let receipt = fake_receipt(&sample_output(), aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+        let info = receipt_info(&receipt).unwrap();
+        assert_eq!(info.kind, "fake");
+        assert_eq!(info.byte_size, receipt_to_bytes(&receipt).unwrap().len());
+    }
+
+    #[test]
+    fn test_plain_receipt_bytes_never_start_with_compressed_tag() {
+        let receipt = fake_receipt(&sample_output(), aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+        let bytes = receipt_to_bytes(&receipt).unwrap();
+        assert_ne!(bytes[0], COMPRESSED_RECEIPT_TAG);
+
+        let restored = receipt_from_bytes_compressed(&bytes).unwrap();
+        assert_eq!(restored.journal.bytes, receipt.journal.bytes);
+    }
+
+    #[test]
+    fn test_receipt_from_bytes_compat_accepts_other_configs() {
+        use bincode::Options;
+        let receipt = fake_receipt(&sample_output(), aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+        let options = bincode::DefaultOptions::new;
+        let encodings = [
+            options().with_fixint_encoding().allow_trailing_bytes().serialize(&receipt).unwrap(),
+            options().with_fixint_encoding().with_big_endian().serialize(&receipt).unwrap(),
+            options().serialize(&receipt).unwrap(),
+            options().with_big_endian().serialize(&receipt).unwrap(),
+        ];
+        assert_eq!(encodings[0], receipt_to_bytes(&receipt).unwrap());
+
+        for (i, bytes) in encodings.iter().enumerate() {
+            assert_eq!(receipt_from_bytes(bytes).is_ok(), i == 0, "encoding {}", i);
+            let restored = receipt_from_bytes_compat(bytes).unwrap();
+            assert_eq!(restored.journal.bytes, receipt.journal.bytes, "encoding {}", i);
+        }
+        assert!(receipt_from_bytes_compat(b"not a receipt").is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_compressed_receipt_roundtrip() {
+        let receipt = fake_receipt(&sample_output(), aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+        let bytes = receipt_to_bytes_compressed(&receipt).unwrap();
+        assert_eq!(bytes[0], COMPRESSED_RECEIPT_TAG);
+
+        let restored = receipt_from_bytes_compressed(&bytes).unwrap();
+        assert_eq!(restored.journal.bytes, receipt.journal.bytes);
+        assert_eq!(
+            decompress_receipt_bytes(&bytes).unwrap().as_ref(),
+            receipt_to_bytes(&receipt).unwrap().as_slice()
+        );
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_decompression_stops_at_receipt_limit() {
+        let bomb = zstd::bulk::compress(&vec![0u8; MAX_RECEIPT_BYTES + 1], ZSTD_LEVEL).unwrap();
+        assert!(bomb.len() < 64 * 1024, "{} bytes", bomb.len());
+        let mut bytes = vec![COMPRESSED_RECEIPT_TAG];
+        bytes.extend_from_slice(&bomb);
+
+        let err = decompress_receipt_bytes(&bytes).unwrap_err();
+        let source = std::error::Error::source(&err).map(ToString::to_string).unwrap_or_default();
+        assert!(source.contains("byte limit"), "{:?}", err);
+        assert!(receipt_from_bytes_compressed(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_cached_prove_skips_second_proof() {
+        let cache = InMemoryReceiptCache::default();
+        let input = guest_input(CLEAR_JSON, "t", "c", "v", "m");
+        let proofs = std::cell::Cell::new(0);
+        let prove = |_: &GuestInput| {
+            proofs.set(proofs.get() + 1);
+            let output = sample_output();
+            Ok((fake_receipt(&output, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap(), output))
+        };
+
+        let (first, _) = cached_prove(&input, &cache, prove).unwrap();
+        let (second, output) = cached_prove(&input, &cache, prove).unwrap();
+
+        assert_eq!(proofs.get(), 1);
+        assert_eq!(second.journal.bytes, first.journal.bytes);
+        assert_eq!(output.concerns_hash, sample_output().concerns_hash);
+
+        let other = guest_input(CLEAR_JSON, "t", "c", "v", "other-model");
+        cached_prove(&other, &cache, prove).unwrap();
+        assert_eq!(proofs.get(), 2);
+    }
+
+    #[test]
+    fn test_input_hash_distinguishes_inputs() {
+        let a = guest_input(CLEAR_JSON, "t", "c", "v", "m");
+        let mut b = a.clone();
+        assert_eq!(input_hash(&a), input_hash(&b));
+        b.card_hash.push('x');
+        assert_ne!(input_hash(&a), input_hash(&b));
+    }
+
+    #[test]
+    fn test_verify_batch_preserves_order() {
+        let receipts: Vec<Receipt> = ["first", "second", "third"]
+            .into_iter()
+            .map(|model| {
+                let output = GuestOutput { model: model.to_string(), ..sample_output() };
+                fake_receipt(&output, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap()
+            })
+            .collect();
+
+        let results = verify_batch(&receipts);
+        assert_eq!(results.len(), receipts.len());
+        if is_dev_mode() {
+            let models: Vec<_> = results.into_iter().map(|r| r.unwrap().model).collect();
+            assert_eq!(models, ["first", "second", "third"]);
+        } else {
+            for result in results {
+                assert!(matches!(result, Err(ProverError::DevModeReceipt)), "{:?}", result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_output_rejects_unknown_schema_version() {
+        let output = GuestOutput {
+            schema_version: JOURNAL_SCHEMA_VERSION + 1,
+            ..sample_output()
+        };
+        let receipt = fake_receipt(&output, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+
+        match decode_output(&receipt) {
+            Err(ProverError::UnsupportedSchemaVersion { found, expected }) => {
+                assert_eq!(found, JOURNAL_SCHEMA_VERSION + 1);
+                assert_eq!(expected, JOURNAL_SCHEMA_VERSION);
+            }
+            other => panic!("expected UnsupportedSchemaVersion, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_output_without_verification() {
+        let output = sample_output();
+        let receipt = fake_receipt(&output, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+
+        let decoded = decode_output(&receipt).expect("Decoding failed");
+        assert_eq!(decoded.verdict, output.verdict);
+        assert_eq!(decoded.concerns_hash, output.concerns_hash);
+        assert_eq!(decoded.model, output.model);
+    }
+
+    #[test]
+    fn test_verify_rejects_fake_receipt_outside_dev_mode() {
+        if is_dev_mode() {
+            return;
+        }
+
+        let receipt = fake_receipt(&sample_output(), aip_zkvm_methods::AIP_ZKVM_GUEST_ID).unwrap();
+        assert!(is_fake_receipt(&receipt));
+        let err = verify_verdict_proof(&receipt).unwrap_err();
+        assert!(err.to_string().contains("dev mode"), "{err}");
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed; run with RISC0_DEV_MODE=1
+    fn test_prove_dev_mode() {
+        assert!(is_dev_mode(), "set RISC0_DEV_MODE=1 to run this test");
+
+        let (receipt, output) = prove_verdict_derivation(
+            BOUNDARY_INJECTION_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Dev-mode proving failed");
+
+        assert!(is_fake_receipt(&receipt));
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::BoundaryViolation);
+        assert_eq!(output.action, aip_zkvm_core::Action::DenyAndEscalate);
+        let verified = verify_verdict_proof(&receipt).expect("Verification failed");
+        assert_eq!(verified.verdict, output.verdict);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_with_assumptions() {
+        let (upstream, _) = prove_verdict_derivation(
+            REVIEW_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Proving failed");
+
+        let input = guest_input(CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model");
+        let (receipt, output) = prove_with_assumptions(&input, vec![upstream.clone()]).expect("Proving failed");
+
+        let expected = aip_zkvm_core::hash_assumptions(&[assumption_from_receipt(&upstream).unwrap()]);
+        assert_eq!(output.assumptions_hash, expected);
+        assert_eq!(verify_verdict_proof(&receipt).expect("Verification failed"), output);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain installed
+    fn test_prove_with_stats() {
+        let (_, output, stats) = prove_verdict_derivation_with_stats(
+            CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Proving failed");
+
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::Clear);
+        assert!(stats.user_cycles > 0);
+        assert!(stats.total_cycles >= stats.user_cycles);
+        assert!(stats.segments >= 1);
+    }
+
+    #[test]
+    #[ignore] // Requires RISC Zero toolchain and Docker installed
+    fn test_compress_clear() {
+        let (receipt, _) = prove_verdict_derivation(
+            CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
+        ).expect("Proving failed");
+        let stark_size = receipt_to_bytes(&receipt).expect("Serialization failed").len();
+
+        let compressed = compress_receipt(&receipt).expect("Compression failed");
+        let bytes = receipt_to_bytes(&compressed).expect("Serialization failed");
+        assert!(bytes.len() * 10 < stark_size, "{} vs {} bytes", bytes.len(), stark_size);
+
+        let restored = receipt_from_bytes(&bytes).expect("Deserialization failed");
+        let output = verify_verdict_proof(&restored).expect("Verification failed");
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::Clear);
+    }
+
     #[test]
     #[ignore] // Requires RISC Zero toolchain installed
     fn test_receipt_roundtrip() {
         let (receipt, _) = prove_verdict_derivation(
-            CLEAR_JSON, "abc123", "def456", "ghi789", "test-model",
+            CLEAR_JSON, THINKING_HASH, CARD_HASH, VALUES_HASH, "test-model",
         ).expect("Proving failed");
 
         let bytes = receipt_to_bytes(&receipt).expect("Serialization failed");
@@ -0,0 +1,111 @@
+//! Token-bucket rate limiting for the proving endpoints.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Buckets kept before full (idle) ones are pruned.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Per-client token buckets: each client may burst up to `capacity` requests,
+/// then gets `refill_per_sec` more per second.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// A limiter allowing bursts of `burst` and `per_minute` sustained
+    /// requests per minute, per client.
+    pub fn new(burst: u32, per_minute: u32) -> Self {
+        Self {
+            capacity: f64::from(burst.max(1)),
+            refill_per_sec: f64::from(per_minute) / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Read `RATE_LIMIT_BURST` (default 10) and `RATE_LIMIT_PER_MINUTE`
+    /// (default 30). A per-minute rate of 0 disables rate limiting.
+    pub fn from_env() -> Option<Self> {
+        let read = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(default)
+        };
+        let per_minute = read("RATE_LIMIT_PER_MINUTE", 30);
+        if per_minute == 0 {
+            return None;
+        }
+        Some(Self::new(read("RATE_LIMIT_BURST", 10), per_minute))
+    }
+
+    /// Take one token for `client`. On refusal, returns how long until a
+    /// token is available.
+    pub fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= PRUNE_THRESHOLD {
+            let (capacity, rate) = (self.capacity, self.refill_per_sec);
+            buckets.retain(|_, b| b.tokens + now.saturating_duration_since(b.last).as_secs_f64() * rate < capacity);
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            last: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_then_refuse() {
+        let limiter = RateLimiter::new(3, 60);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check("a", now).is_ok());
+        }
+        let retry_after = limiter.check("a", now).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_refills_over_time() {
+        let limiter = RateLimiter::new(1, 60);
+        let now = Instant::now();
+        assert!(limiter.check("a", now).is_ok());
+        assert!(limiter.check("a", now + Duration::from_millis(500)).is_err());
+        assert!(limiter.check("a", now + Duration::from_secs(2)).is_ok());
+    }
+
+    #[test]
+    fn test_clients_are_independent() {
+        let limiter = RateLimiter::new(1, 60);
+        let now = Instant::now();
+        assert!(limiter.check("a", now).is_ok());
+        assert!(limiter.check("a", now).is_err());
+        assert!(limiter.check("b", now).is_ok());
+    }
+}
@@ -2,29 +2,114 @@
 //!
 //! Routes:
 //!   POST /prove        — accept proof request, spawn proving task
+//!   POST /prove/sync   — prove and wait, returning the verdict (504 past the proof timeout)
 //!   GET  /prove/:id    — proof status
-//!   POST /prove/verify — verify a receipt
-//!   GET  /health       — health check
+//!   GET  /prove/:id/receipt — stored receipt bytes (base64 JSON on `Accept: application/json`,
+//!                         an `.aipproof` bundle with `?format=bundle`)
+//!   POST /prove/verify — verify a receipt or `.aipproof` bundle
+//!   GET  /proofs       — list proofs, newest first (`?status=&limit=&cursor=`)
+//!   POST /prove/:id/verify — verify the stored receipt of a completed proof
+//!   GET  /health       — liveness (always 200 while the process serves)
+//!   GET  /ready        — readiness (503 unless Postgres answers and the guest can be proven)
+//!   GET  /capabilities — guest image ID and ELF hash
+//!
+//! Every request runs in a span carrying its `X-Request-Id` (taken from the
+//! request or generated), which is echoed in the response; proving-task logs
+//! repeat it so they can be tied back to the request.
+//!
+//! `POST /prove`, `POST /prove/sync` and `POST /prove/verify` are rate limited per prover key
+//! (per client IP when auth is disabled); see [`crate::rate_limit`].
+//!
+//! A `/prove` request may carry a `callback_url`; once the proof completes or
+//! fails, its status is POSTed there, signed with `X-Signature` (hex
+//! HMAC-SHA256 of the body, keyed with the prover key) when one is configured.
 
 use axum::{
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, DefaultBodyLimit, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info, warn};
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::{error, info, info_span, warn, Instrument};
 
+use crate::bundle::{is_bundle, read_bundle, write_bundle, ProofBundle};
+use crate::metrics::Metrics;
 use crate::prover;
+use crate::rate_limit::RateLimiter;
+
+static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Default number of proofs allowed to run at once (`MAX_CONCURRENT_PROOFS`).
+pub const DEFAULT_MAX_CONCURRENT_PROOFS: usize = 2;
+
+/// Page size for `GET /proofs` when `limit` is not given.
+const DEFAULT_LIST_LIMIT: i64 = 20;
+/// Largest page `GET /proofs` returns; larger `limit`s are clamped to it.
+pub const MAX_LIST_LIMIT: i64 = 100;
+
+/// Delivery attempts per callback; the delay doubles after each failure.
+const CALLBACK_ATTEMPTS: u32 = 3;
+const CALLBACK_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Default request body limit in bytes (`MAX_REQUEST_BODY_BYTES`).
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Largest receipt accepted by the verify endpoints, in bytes. Checked on the
+/// base64 length before decoding, and on a compressed receipt's output while
+/// it decompresses (`prover::decompress_receipt_bytes`), so neither step
+/// allocates much past it.
+pub use crate::prover::MAX_RECEIPT_BYTES;
+
+/// Largest `analysis_json` accepted by `POST /prove`, in bytes — the same
+/// limit the guest enforces.
+pub use aip_zkvm_core::MAX_ANALYSIS_JSON_LEN;
+
+/// Default limit on a single proving run (`PROOF_TIMEOUT_SECS`).
+pub const DEFAULT_PROOF_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long `/ready` waits for the database probe before reporting not ready.
+const HEALTH_DB_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Shared application state.
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub prover_key: Option<String>,
+    /// Permits for proving tasks. Each proof holds one until its proving
+    /// thread finishes, even past `proof_timeout`, so at most this many ~3GB
+    /// proving jobs run at once; the rest wait.
+    pub proving_permits: Arc<Semaphore>,
+    /// Request bodies larger than this are rejected with 413 before being
+    /// buffered.
+    pub max_body_bytes: usize,
+    /// Limits `POST /prove` and `POST /prove/verify`; `None` disables it.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// A proof still running after this long is marked failed. Its proving
+    /// thread can't be interrupted, so the permit is only released once that
+    /// thread finishes.
+    pub proof_timeout: Duration,
+    /// Verify each receipt before persisting it (`SELF_VERIFY`, default on).
+    /// When off, proofs are stored with `verified = false`.
+    pub self_verify: bool,
+    /// Proves and verifies receipts: `prover::RiscZeroProver` when serving,
+    /// `prover::MockProver` in tests.
+    pub prover: Arc<dyn prover::Prover>,
+    /// Gauges served on `/metrics`.
+    pub metrics: Arc<Metrics>,
 }
 
 /// Proof request payload from the API worker.
@@ -37,6 +122,9 @@ pub struct ProofRequest {
     pub card_hash: String,
     pub values_hash: String,
     pub model: String,
+    /// Where to POST the final `ProofStatusResponse`, instead of polling.
+    #[serde(default)]
+    pub callback_url: Option<String>,
 }
 
 /// Proof response.
@@ -52,14 +140,73 @@ pub struct ProofStatusResponse {
     pub proof_id: String,
     pub status: String,
     pub proving_duration_ms: Option<i32>,
+    /// Guest execution cycles, excluding paging and padding.
+    pub user_cycles: Option<i64>,
+    /// Size of the stored receipt, after compression if enabled.
+    pub receipt_size_bytes: Option<i32>,
     pub verified: bool,
     pub error_message: Option<String>,
 }
 
+/// Response to `POST /prove/sync`: the proof's final status plus its
+/// verdict as `POST /prove/verify` would report it.
+#[derive(Serialize)]
+pub struct ProveSyncResponse {
+    #[serde(flatten)]
+    pub status: ProofStatusResponse,
+    #[serde(flatten)]
+    pub result: VerifyResponse,
+}
+
+/// Receipt response for `Accept: application/json`.
+#[derive(Serialize)]
+pub struct ReceiptResponse {
+    pub proof_id: String,
+    pub receipt: String, // base64-encoded receipt bytes
+}
+
+/// Query parameters for `GET /prove/:id/receipt`.
+#[derive(Deserialize)]
+pub struct ReceiptQuery {
+    /// `bundle` to wrap the receipt in an `.aipproof` bundle; a bare receipt
+    /// otherwise.
+    pub format: Option<String>,
+}
+
+/// Query parameters for `GET /proofs`.
+#[derive(Deserialize)]
+pub struct ListProofsQuery {
+    /// Only list proofs with this status.
+    pub status: Option<String>,
+    /// Page size, 1 to MAX_LIST_LIMIT.
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<String>,
+}
+
+/// One proof in a `GET /proofs` page.
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ProofListItem {
+    pub proof_id: String,
+    pub status: String,
+    pub proving_duration_ms: Option<i32>,
+    pub verified: bool,
+    pub error_message: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A page of proofs, newest first.
+#[derive(Serialize)]
+pub struct ListProofsResponse {
+    pub proofs: Vec<ProofListItem>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
 /// Verify request.
 #[derive(Deserialize)]
 pub struct VerifyRequest {
-    pub receipt: String, // base64-encoded receipt bytes
+    pub receipt: String, // base64-encoded receipt or `.aipproof` bundle bytes
     pub image_id: Option<String>,
 }
 
@@ -70,9 +217,68 @@ pub struct VerifyResponse {
     pub verdict: Option<String>,
     pub action: Option<String>,
     pub concerns_hash: Option<String>,
+    /// Evidence truncation length, in bytes, that produced `concerns_hash`.
+    pub max_evidence_length: Option<u32>,
+    /// Hash of the verdict policy the proof was derived under.
+    pub policy_hash: Option<String>,
+    /// The model's stated confidence, in basis points (0-10000).
+    pub confidence_bps: Option<u16>,
+    /// Pass-through commitments, for checking the proof covers the expected
+    /// inputs.
+    pub thinking_hash: Option<String>,
+    pub card_hash: Option<String>,
+    pub values_hash: Option<String>,
+    pub model: Option<String>,
+    /// `hash_assumptions` of the upstream proofs this one composes with;
+    /// empty if none.
+    pub assumptions_hash: Option<String>,
+    /// Whether the model's stated verdict matched the derived one.
+    pub stated_verdict_matches: Option<bool>,
     pub error: Option<String>,
 }
 
+impl VerifyResponse {
+    /// A valid result reporting `output`.
+    fn from_output(output: aip_zkvm_core::GuestOutput) -> Self {
+        VerifyResponse {
+            valid: true,
+            verdict: Some(output.verdict.to_string()),
+            action: Some(output.action.to_string()),
+            concerns_hash: Some(output.concerns_hash),
+            max_evidence_length: Some(output.max_evidence_length),
+            policy_hash: Some(output.policy_hash),
+            confidence_bps: Some(output.confidence_bps),
+            thinking_hash: Some(output.thinking_hash),
+            card_hash: Some(output.card_hash),
+            values_hash: Some(output.values_hash),
+            model: Some(output.model),
+            assumptions_hash: Some(output.assumptions_hash),
+            stated_verdict_matches: Some(output.stated_verdict_matches),
+            error: None,
+        }
+    }
+
+    /// An invalid result carrying only `error`.
+    fn failure(error: String) -> Self {
+        VerifyResponse {
+            valid: false,
+            verdict: None,
+            action: None,
+            concerns_hash: None,
+            max_evidence_length: None,
+            policy_hash: None,
+            confidence_bps: None,
+            thinking_hash: None,
+            card_hash: None,
+            values_hash: None,
+            model: None,
+            assumptions_hash: None,
+            stated_verdict_matches: None,
+            error: Some(error),
+        }
+    }
+}
+
 /// Health check response.
 #[derive(Serialize)]
 pub struct HealthResponse {
@@ -80,64 +286,376 @@ pub struct HealthResponse {
     pub version: String,
 }
 
+/// Readiness response — which dependencies of `POST /prove` are usable.
+#[derive(Serialize)]
+pub struct ReadyResponse {
+    pub status: String,
+    pub version: String,
+    pub database: bool,
+    pub prover: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Capabilities response — identifies the guest program this service proves with.
+#[derive(Serialize)]
+pub struct CapabilitiesResponse {
+    pub version: String,
+    pub image_id: String,
+    pub guest_elf_sha256: String,
+}
+
 /// Build the Axum router.
 pub fn build_router(state: AppState) -> Router {
-    Router::new()
+    let max_body_bytes = state.max_body_bytes;
+    let state = Arc::new(state);
+
+    let proving = Router::new()
         .route("/prove", post(handle_prove))
-        .route("/prove/{id}", get(handle_proof_status))
+        .route("/prove/sync", post(handle_prove_sync))
         .route("/prove/verify", post(handle_verify))
+        .route("/prove/:id/verify", post(handle_verify_stored))
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit));
+
+    // Unauthenticated and read-only: verification only, no DB access.
+    let public = Router::new()
+        .route("/public/verify", post(handle_public_verify))
+        .route_layer(middleware::from_fn_with_state(state.clone(), public_rate_limit));
+
+    Router::new()
+        .merge(proving)
+        .merge(public)
+        .route("/prove/:id", get(handle_proof_status))
+        .route("/proofs", get(handle_list_proofs))
+        .route("/prove/:id/receipt", get(handle_proof_receipt))
+        .route("/prove/:id/requeue", post(handle_requeue))
         .route("/health", get(handle_health))
+        .route("/ready", get(handle_ready))
+        .route("/metrics", get(handle_metrics))
+        .route("/capabilities", get(handle_capabilities))
+        // Replace axum's fixed 2MB extractor limit with the configured one.
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(max_body_bytes))
         .layer(CorsLayer::permissive())
-        .with_state(Arc::new(state))
+        // gzip/deflate per `Accept-Encoding`; receipt bytes compress well.
+        .layer(CompressionLayer::new())
+        .layer(middleware::from_fn(request_id))
+        .with_state(state)
 }
 
-/// Authenticate requests using X-Prover-Key header.
+/// Authenticate requests against the configured prover key.
+///
+/// Accepts either `X-Prover-Key: <key>` or `Authorization: Bearer <key>`.
+/// If `X-Prover-Key` is present it takes precedence: the bearer token is then
+/// ignored, so a wrong `X-Prover-Key` is rejected even with a valid token.
 fn check_auth(headers: &HeaderMap, state: &AppState) -> Result<(), StatusCode> {
-    if let Some(expected) = &state.prover_key {
-        match headers.get("X-Prover-Key") {
-            Some(key) if key.to_str().unwrap_or("") == expected => Ok(()),
-            _ => Err(StatusCode::UNAUTHORIZED),
+    let expected = match &state.prover_key {
+        Some(expected) => expected,
+        None => return Ok(()), // No key configured = auth disabled
+    };
+
+    match provided_key(headers) {
+        Some(key) if keys_match(key, expected) => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+/// The key a client presented: `X-Prover-Key`, else the bearer token.
+fn provided_key(headers: &HeaderMap) -> Option<&str> {
+    match headers.get("X-Prover-Key") {
+        Some(key) => key.to_str().ok(),
+        None => headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer ")),
+    }
+}
+
+/// Correlation ID for a request, from `X-Request-Id` or freshly generated.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Request-ID middleware: runs the request in a span with its ID, exposes it
+/// to handlers as an [`Extension`], and echoes it in `X-Request-Id`.
+async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+    let span = info_span!("request", request_id = %id, method = %request.method(), path = %request.uri().path());
+    let mut response = next.run(request).instrument(span).await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    }
+    response
+}
+
+/// Rate-limit middleware for the proving endpoints: 429 with `Retry-After`
+/// once a client's bucket is empty.
+async fn rate_limit(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    if let Some(limiter) = &state.rate_limiter {
+        let client = match (&state.prover_key, provided_key(request.headers())) {
+            (Some(_), Some(key)) => format!("key:{}", key),
+            _ => format!("ip:{}", client_ip(&request)),
+        };
+
+        if let Some(rejection) = check_rate_limit(limiter, &client) {
+            return rejection;
         }
-    } else {
-        Ok(()) // No key configured = auth disabled
     }
+
+    next.run(request).await
+}
+
+/// Rate-limit middleware for the public endpoints. Always keyed on the
+/// client IP: callers are unauthenticated, so a presented key proves nothing
+/// and must not buy a fresh bucket.
+async fn public_rate_limit(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(limiter) = &state.rate_limiter {
+        let client = format!("public-ip:{}", client_ip(&request));
+        if let Some(rejection) = check_rate_limit(limiter, &client) {
+            return rejection;
+        }
+    }
+
+    next.run(request).await
+}
+
+fn client_ip(request: &Request) -> String {
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => addr.ip().to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// The 429 response for `client`, if its bucket is empty.
+fn check_rate_limit(limiter: &RateLimiter, client: &str) -> Option<Response> {
+    let retry_after = limiter.check(client, Instant::now()).err()?;
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    warn!(retry_after_secs = secs, "Rate limit exceeded");
+    Some(
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, secs.to_string())],
+            "Rate limit exceeded",
+        )
+            .into_response(),
+    )
+}
+
+/// Constant-time key comparison, so response timing doesn't reveal how much
+/// of the key matched.
+///
+/// Slice `ct_eq` returns early on a length mismatch, so both sides are hashed
+/// to fixed-length digests first; that keeps the key's length secret too.
+fn keys_match(provided: &str, expected: &str) -> bool {
+    use sha2::Digest;
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided.ct_eq(&expected).into()
 }
 
 /// POST /prove — accept a proof request and spawn a background task.
 async fn handle_prove(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    Extension(RequestId(request_id)): Extension<RequestId>,
     Json(req): Json<ProofRequest>,
-) -> Result<Json<ProofResponse>, StatusCode> {
-    check_auth(&headers, &state)?;
+) -> Result<Json<ProofResponse>, (StatusCode, String)> {
+    check_auth(&headers, &state).map_err(|code| (code, "Unauthorized".to_string()))?;
+    check_analysis_len(&req)?;
 
     info!(proof_id = %req.proof_id, checkpoint_id = %req.checkpoint_id, "Received proof request");
 
-    // Update status to 'proving'
-    let _ = sqlx::query("UPDATE verdict_proofs SET status = 'proving', updated_at = now() WHERE proof_id = $1")
-        .bind(&req.proof_id)
-        .execute(&state.db)
-        .await;
+    if let Some(status) = claim_proof(&state, &req.proof_id).await? {
+        return Ok(Json(ProofResponse {
+            proof_id: req.proof_id,
+            status,
+        }));
+    }
+
+    let proof_id = req.proof_id.clone();
+    spawn_proof(&state, req, request_id);
+
+    Ok(Json(ProofResponse {
+        proof_id,
+        status: "proving".to_string(),
+    }))
+}
+
+/// POST /prove/sync — prove inline and return the result in the response.
+///
+/// For callers that would rather wait than poll. The proof is claimed, run
+/// and persisted exactly as for `POST /prove`; this just waits for it. If it
+/// hasn't finished within the proof timeout (which includes waiting for a
+/// proving slot), responds 504 with a `ProofResponse`: proving carries on in
+/// the background and the row is completed or failed as usual, so the client
+/// can fall back to `GET /prove/:id`. A proof that is already proving or
+/// finished gets 409 with its current status.
+async fn handle_prove_sync(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    Json(req): Json<ProofRequest>,
+) -> Result<Json<ProveSyncResponse>, Response> {
+    check_auth(&headers, &state).map_err(|code| (code, "Unauthorized".to_string()).into_response())?;
+    check_analysis_len(&req).map_err(IntoResponse::into_response)?;
+
+    info!(proof_id = %req.proof_id, checkpoint_id = %req.checkpoint_id, "Received synchronous proof request");
+
+    let proof_id = req.proof_id.clone();
+    if let Some(status) = claim_proof(&state, &proof_id).await.map_err(IntoResponse::into_response)? {
+        return Err((StatusCode::CONFLICT, Json(ProofResponse { proof_id, status })).into_response());
+    }
+
+    let task = spawn_proof(&state, req, request_id);
+    let (status, output) = match tokio::time::timeout(state.proof_timeout, task).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Proving task failed: {}", e)).into_response())
+        }
+        Err(_) => {
+            warn!(proof_id = %proof_id, "Synchronous proof still running at timeout; continuing in background");
+            let pending = ProofResponse {
+                proof_id,
+                status: "proving".to_string(),
+            };
+            return Err((StatusCode::GATEWAY_TIMEOUT, Json(pending)).into_response());
+        }
+    };
+
+    let result = match output {
+        Some(output) if status.verified => VerifyResponse::from_output(output),
+        Some(output) => VerifyResponse {
+            valid: false,
+            error: Some("Receipt was not self-verified".to_string()),
+            ..VerifyResponse::from_output(output)
+        },
+        None => VerifyResponse::failure(status.error_message.clone().unwrap_or_default()),
+    };
+    Ok(Json(ProveSyncResponse { status, result }))
+}
+
+/// Reject an `analysis_json` the guest would refuse, before claiming the proof.
+fn check_analysis_len(req: &ProofRequest) -> Result<(), (StatusCode, String)> {
+    if req.analysis_json.len() > MAX_ANALYSIS_JSON_LEN {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "analysis_json is {} bytes; the limit is {} bytes",
+                req.analysis_json.len(),
+                MAX_ANALYSIS_JSON_LEN
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Claim the proof by moving it to 'proving'.
+///
+/// The conditional update is atomic, so a retried or duplicated request for
+/// a proof that is already proving or finished gets `Some` of its current
+/// status instead of a second job. `None` means the caller should prove.
+async fn claim_proof(state: &AppState, proof_id: &str) -> Result<Option<String>, (StatusCode, String)> {
+    let claimed = sqlx::query(
+        "UPDATE verdict_proofs SET status = 'proving', updated_at = now() \
+         WHERE proof_id = $1 AND status NOT IN ('proving', 'completed', 'failed')"
+    )
+    .bind(proof_id)
+    .execute(&state.db)
+    .await
+    .map(|r| r.rows_affected() > 0)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+
+    if claimed {
+        return Ok(None);
+    }
+
+    let existing = sqlx::query_as::<_, (String,)>("SELECT status FROM verdict_proofs WHERE proof_id = $1")
+        .bind(proof_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
 
-    // Spawn proving task in background
+    // No row at all: prove anyway, as before.
+    Ok(existing.map(|(status,)| {
+        info!(proof_id = %proof_id, status = %status, "Duplicate proof request; not re-proving");
+        status
+    }))
+}
+
+/// Spawn the proving task for a claimed proof.
+///
+/// The task waits for a proving slot, proves, persists the outcome and
+/// delivers any callback. It resolves to the final status and, on success,
+/// the proven output; `POST /prove` detaches it and `POST /prove/sync` waits
+/// on it.
+fn spawn_proof(
+    state: &AppState,
+    req: ProofRequest,
+    request_id: String,
+) -> tokio::task::JoinHandle<(ProofStatusResponse, Option<aip_zkvm_core::GuestOutput>)> {
     let db = state.db.clone();
+    let permits = state.proving_permits.clone();
+    let prover_key = state.prover_key.clone();
+    let proof_timeout = state.proof_timeout;
+    let self_verify_enabled = state.self_verify;
+    let zk_prover = state.prover.clone();
     let proof_id = req.proof_id.clone();
+    let span = proof_span(&proof_id);
     tokio::spawn(async move {
+        // Wait for a proving slot; the proof stays 'proving' while queued.
+        let permit = match permits.acquire_owned().await {
+            Ok(permit) => permit,
+            // Semaphore closed: shutting down.
+            Err(_) => {
+                let message = "Proving service shut down".to_string();
+                let _ = sqlx::query("SELECT fail_proof($1, $2)")
+                    .bind(&proof_id)
+                    .bind(&message)
+                    .execute(&db)
+                    .await;
+                return (failed_status(&proof_id, message), None);
+            }
+        };
         let start = std::time::Instant::now();
 
-        match prover::prove_verdict_derivation(
-            &req.analysis_json,
-            &req.thinking_hash,
-            &req.card_hash,
-            &req.values_hash,
-            &req.model,
-        ) {
-            Ok((receipt, output)) => {
+        let (analysis_json, thinking_hash, card_hash, values_hash, model) = (
+            req.analysis_json.clone(),
+            req.thinking_hash.clone(),
+            req.card_hash.clone(),
+            req.values_hash.clone(),
+            req.model.clone(),
+        );
+        let blocking_prover = zk_prover.clone();
+        let proved = prove_blocking(proof_timeout, permit, move || {
+            blocking_prover.prove(
+                &analysis_json,
+                &thinking_hash,
+                &card_hash,
+                &values_hash,
+                &model,
+            )
+        })
+        .await;
+
+        let (status, output) = match proved {
+            Ok((receipt, output, stats)) => {
                 let duration_ms = start.elapsed().as_millis() as i32;
-                let receipt_bytes = match prover::receipt_to_bytes(&receipt) {
+                let receipt_bytes = match stored_receipt_bytes(&receipt) {
                     Ok(b) => b,
                     Err(e) => {
-                        error!(proof_id = %proof_id, "Failed to serialize receipt: {}", e);
+                        error!(proof_id = %proof_id, request_id = %request_id, "Failed to serialize receipt: {}", e);
                         let _ = sqlx::query(
                             "SELECT fail_proof($1, $2)"
                         )
@@ -145,31 +663,37 @@ async fn handle_prove(
                         .bind(format!("Receipt serialization failed: {}", e))
                         .execute(&db)
                         .await;
-                        return;
+                        let status = failed_status(&proof_id, format!("Receipt serialization failed: {}", e));
+                        if let Some(url) = &req.callback_url {
+                            deliver_callback(url, &status, prover_key.as_deref()).await;
+                        }
+                        return (status, None);
                     }
                 };
 
                 let journal_bytes = receipt.journal.bytes.clone();
-                let verdict_str = serde_json::to_string(&output.verdict).unwrap_or_default();
-                let image_id_hex: String = aip_zkvm_methods::AIP_ZKVM_GUEST_ID
-                    .iter()
-                    .flat_map(|w| w.to_le_bytes())
-                    .map(|b| format!("{:02x}", b))
-                    .collect();
+                let receipt_size_bytes = receipt_bytes.len() as i32;
+                let verdict_str = output.verdict.to_string();
+                let image_id_hex = prover::guest_image_id_hex();
 
                 // Self-verify before writing
-                let verified = prover::verify_verdict_proof(&receipt).is_ok();
+                let verified = self_verify(self_verify_enabled, zk_prover.as_ref(), &receipt, &output, &stats);
 
                 info!(
                     proof_id = %proof_id,
+                    request_id = %request_id,
                     verdict = %verdict_str,
                     duration_ms = duration_ms,
+                    user_cycles = stats.user_cycles,
+                    total_cycles = stats.total_cycles,
+                    segments = stats.segments,
+                    receipt_size_bytes = receipt_size_bytes,
                     verified = verified,
                     "Proof completed"
                 );
 
                 match sqlx::query(
-                    "SELECT complete_proof($1, $2, $3, $4, $5, $6::numeric, $7, $8)"
+                    "SELECT complete_proof($1, $2, $3, $4, $5, $6::numeric, $7, $8, $9, $10)"
                 )
                 .bind(&proof_id)
                 .bind(&image_id_hex)
@@ -179,29 +703,186 @@ async fn handle_prove(
                 .bind(0.005f64) // estimated cost — explicit ::numeric cast for sqlx
                 .bind(verified)
                 .bind(if verified { Some(chrono::Utc::now()) } else { None })
+                .bind(stats.user_cycles as i64)
+                .bind(receipt_size_bytes)
                 .execute(&db)
+                .instrument(info_span!("db_write"))
                 .await {
-                    Ok(_) => info!(proof_id = %proof_id, "Proof persisted to DB"),
-                    Err(e) => error!(proof_id = %proof_id, "Failed to persist proof: {}", e),
+                    Ok(_) => info!(proof_id = %proof_id, request_id = %request_id, "Proof persisted to DB"),
+                    Err(e) => error!(proof_id = %proof_id, request_id = %request_id, "Failed to persist proof: {}", e),
                 }
+
+                let status = ProofStatusResponse {
+                    proof_id: proof_id.clone(),
+                    status: "completed".to_string(),
+                    proving_duration_ms: Some(duration_ms),
+                    user_cycles: Some(stats.user_cycles as i64),
+                    receipt_size_bytes: Some(receipt_size_bytes),
+                    verified,
+                    error_message: None,
+                };
+                (status, Some(output))
             }
-            Err(e) => {
-                error!(proof_id = %proof_id, "Proving failed: {}", e);
+            Err(message) => {
+                error!(proof_id = %proof_id, request_id = %request_id, "{}", message);
                 let _ = sqlx::query(
                     "SELECT fail_proof($1, $2)"
                 )
                 .bind(&proof_id)
-                .bind(format!("Proving failed: {}", e))
+                .bind(&message)
                 .execute(&db)
                 .await;
+                (failed_status(&proof_id, message), None)
             }
+        };
+
+        if let Some(url) = &req.callback_url {
+            deliver_callback(url, &status, prover_key.as_deref()).await;
         }
+        (status, output)
+    }.instrument(span))
+}
+
+/// Receipt bytes as persisted to `verdict_proofs.receipt`: zstd-compressed
+/// when built with the `compression` feature. Readers go through
+/// `prover::decompress_receipt_bytes`, which accepts either form.
+fn stored_receipt_bytes(receipt: &risc0_zkvm::Receipt) -> prover::Result<Vec<u8>> {
+    #[cfg(feature = "compression")]
+    {
+        prover::receipt_to_bytes_compressed(receipt)
+    }
+    #[cfg(not(feature = "compression"))]
+    {
+        prover::receipt_to_bytes(receipt)
+    }
+}
+
+/// Run a blocking proving call on the blocking pool, giving up after
+/// `timeout`.
+///
+/// The error is the message to record with `fail_proof`. A timed-out proof
+/// can't be interrupted: its thread runs to completion and the result is
+/// dropped. `permit` moves onto that thread, so the proving slot stays taken
+/// until the work really stops, not just until the caller gives up.
+async fn prove_blocking<T, F>(
+    timeout: Duration,
+    permit: OwnedSemaphorePermit,
+    prove: F,
+) -> Result<T, String>
+where
+    F: FnOnce() -> prover::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    // Carry the caller's span onto the blocking thread so the prover's phase
+    // spans nest under the proof.
+    let span = tracing::Span::current();
+    let task = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        span.in_scope(prove)
     });
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(Ok(value))) => Ok(value),
+        Ok(Ok(Err(e))) => Err(format!("Proving failed: {}", e)),
+        Ok(Err(e)) => Err(format!("Proving task panicked: {}", e)),
+        Err(_) => Err(format!("Proving timed out after {}s", timeout.as_secs())),
+    }
+}
 
-    Ok(Json(ProofResponse {
-        proof_id: req.proof_id,
-        status: "proving".to_string(),
-    }))
+/// Root span for one proof attempt. `verdict` and `user_cycles` are
+/// recorded by `self_verify` once proving succeeds; the prover's phase spans
+/// (`build_env`, `prove`, `decode_journal`) and `self_verify`/`db_write`
+/// nest under it.
+fn proof_span(proof_id: &str) -> tracing::Span {
+    info_span!(
+        "proof",
+        proof_id = %proof_id,
+        verdict = tracing::field::Empty,
+        user_cycles = tracing::field::Empty,
+    )
+}
+
+/// Verify our own receipt before persisting it, recording the proof's
+/// outcome on the current `proof` span.
+///
+/// When `enabled` is false (`SELF_VERIFY=0`) the check is skipped and this
+/// returns false: nothing has verified the receipt, so it is not recorded
+/// as verified.
+fn self_verify(
+    enabled: bool,
+    zk_prover: &dyn prover::Prover,
+    receipt: &risc0_zkvm::Receipt,
+    output: &aip_zkvm_core::GuestOutput,
+    stats: &prover::ProveStats,
+) -> bool {
+    let span = tracing::Span::current();
+    span.record("verdict", tracing::field::display(&output.verdict));
+    span.record("user_cycles", stats.user_cycles);
+    if !enabled {
+        return false;
+    }
+    info_span!("self_verify")
+        .in_scope(|| zk_prover.verify(receipt, aip_zkvm_methods::AIP_ZKVM_GUEST_ID).is_ok())
+}
+
+fn failed_status(proof_id: &str, error_message: String) -> ProofStatusResponse {
+    ProofStatusResponse {
+        proof_id: proof_id.to_string(),
+        status: "failed".to_string(),
+        proving_duration_ms: None,
+        user_cycles: None,
+        receipt_size_bytes: None,
+        verified: false,
+        error_message: Some(error_message),
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` keyed with the prover key, sent as `X-Signature`.
+fn sign_payload(key: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// POST the final proof status to `url`, retrying with exponential backoff.
+///
+/// Delivery is best-effort: the proof is already persisted, so failures are
+/// only logged and the caller can still poll `GET /prove/:id`.
+async fn deliver_callback(url: &str, status: &ProofStatusResponse, prover_key: Option<&str>) {
+    let body = match serde_json::to_vec(status) {
+        Ok(b) => b,
+        Err(e) => {
+            error!(proof_id = %status.proof_id, "Failed to serialize callback payload: {}", e);
+            return;
+        }
+    };
+
+    let client = reqwest::Client::new();
+    let mut backoff = CALLBACK_INITIAL_BACKOFF;
+    for attempt in 1..=CALLBACK_ATTEMPTS {
+        let mut request = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .timeout(Duration::from_secs(10))
+            .body(body.clone());
+        if let Some(key) = prover_key {
+            request = request.header("X-Signature", sign_payload(key, &body));
+        }
+
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => {
+                info!(proof_id = %status.proof_id, attempt = attempt, "Callback delivered");
+                return;
+            }
+            Err(e) => warn!(proof_id = %status.proof_id, attempt = attempt, "Callback delivery failed: {}", e),
+        }
+
+        if attempt < CALLBACK_ATTEMPTS {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    error!(proof_id = %status.proof_id, url = %url, "Giving up on callback after {} attempts", CALLBACK_ATTEMPTS);
 }
 
 /// GET /prove/:id — get proof status.
@@ -209,8 +890,9 @@ async fn handle_proof_status(
     State(state): State<Arc<AppState>>,
     Path(proof_id): Path<String>,
 ) -> Result<Json<ProofStatusResponse>, StatusCode> {
-    let row = sqlx::query_as::<_, (String, String, Option<i32>, bool, Option<String>)>(
-        "SELECT proof_id, status, proving_duration_ms, verified, error_message FROM verdict_proofs WHERE proof_id = $1"
+    let row = sqlx::query_as::<_, (String, String, Option<i32>, Option<i64>, Option<i32>, bool, Option<String>)>(
+        "SELECT proof_id, status, proving_duration_ms, user_cycles, receipt_size_bytes, verified, error_message \
+         FROM verdict_proofs WHERE proof_id = $1"
     )
     .bind(&proof_id)
     .fetch_optional(&state.db)
@@ -218,10 +900,12 @@ async fn handle_proof_status(
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     match row {
-        Some((id, status, duration, verified, error)) => Ok(Json(ProofStatusResponse {
+        Some((id, status, duration, user_cycles, receipt_size_bytes, verified, error)) => Ok(Json(ProofStatusResponse {
             proof_id: id,
             status,
             proving_duration_ms: duration,
+            user_cycles,
+            receipt_size_bytes,
             verified,
             error_message: error,
         })),
@@ -229,68 +913,304 @@ async fn handle_proof_status(
     }
 }
 
+/// GET /prove/:id/receipt — the stored receipt, for client-side verification.
+///
+/// Returns raw bincode bytes as `application/octet-stream`, or base64 in a
+/// JSON body when the client sends `Accept: application/json`. With
+/// `?format=bundle` the receipt is wrapped in an `.aipproof` bundle carrying
+/// the stored image ID. 404 unless the proof has completed.
+async fn handle_proof_receipt(
+    State(state): State<Arc<AppState>>,
+    Path(proof_id): Path<String>,
+    Query(query): Query<ReceiptQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let bundle = match query.format.as_deref() {
+        None | Some("receipt") => false,
+        Some("bundle") => true,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let row = sqlx::query_as::<_, (Option<Vec<u8>>, Option<String>)>(
+        "SELECT receipt, image_id FROM verdict_proofs WHERE proof_id = $1 AND status = 'completed'"
+    )
+    .bind(&proof_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let receipt_bytes = match row {
+        Some((Some(bytes), _)) if !bundle => prover::decompress_receipt_bytes(&bytes)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .into_owned(),
+        Some((Some(bytes), image_id)) => {
+            stored_receipt_bundle(&bytes, image_id.as_deref()).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        }
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+
+    if wants_json(&headers) {
+        use base64_engine::*;
+        return Ok(Json(ReceiptResponse {
+            proof_id,
+            receipt: STANDARD.encode(&receipt_bytes),
+        })
+        .into_response());
+    }
+
+    Ok(([(header::CONTENT_TYPE, "application/octet-stream")], receipt_bytes).into_response())
+}
+
+/// Wrap a stored receipt in an `.aipproof` bundle under its stored image ID
+/// (hex), or the built-in guest's when none was recorded. The schema version
+/// is read from the journal, so older proofs are labelled correctly.
+fn stored_receipt_bundle(stored: &[u8], image_id: Option<&str>) -> prover::Result<Vec<u8>> {
+    let receipt = prover::receipt_from_bytes_compressed(stored)?;
+    let image_id = match image_id.filter(|id| !id.is_empty()) {
+        Some(image_id_hex) => prover::image_id_from_hex(image_id_hex)?,
+        None => aip_zkvm_methods::AIP_ZKVM_GUEST_ID,
+    };
+    let schema_version =
+        aip_zkvm_core::journal_schema_version(&receipt.journal.bytes).unwrap_or(aip_zkvm_core::JOURNAL_SCHEMA_VERSION);
+    write_bundle(&ProofBundle {
+        image_id,
+        schema_version,
+        receipt,
+    })
+}
+
+/// POST /prove/:id/requeue — put a failed proof back in the retry queue.
+///
+/// Resets the row to 'pending' with a fresh retry budget and bumps
+/// `manual_retry_count`; the retry loop proves it on its next pass. Only
+/// 'failed' proofs can be requeued.
+async fn handle_requeue(
+    State(state): State<Arc<AppState>>,
+    Path(proof_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ProofResponse>, (StatusCode, String)> {
+    check_auth(&headers, &state).map_err(|code| (code, "Unauthorized".to_string()))?;
+
+    let requeued = sqlx::query(
+        "UPDATE verdict_proofs \
+         SET status = 'pending', retry_count = 0, manual_retry_count = manual_retry_count + 1, \
+             error_message = NULL, updated_at = now() \
+         WHERE proof_id = $1 AND status = 'failed'"
+    )
+    .bind(&proof_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?
+    .rows_affected()
+        > 0;
+
+    if requeued {
+        info!(proof_id = %proof_id, "Requeued failed proof");
+        return Ok(Json(ProofResponse {
+            proof_id,
+            status: "pending".to_string(),
+        }));
+    }
+
+    let existing = sqlx::query_as::<_, (String,)>("SELECT status FROM verdict_proofs WHERE proof_id = $1")
+        .bind(&proof_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+
+    match existing {
+        Some((status,)) => Err((
+            StatusCode::CONFLICT,
+            format!("Proof is '{}'; only failed proofs can be requeued", status),
+        )),
+        None => Err((StatusCode::NOT_FOUND, "Proof not found".to_string())),
+    }
+}
+
+fn wants_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"))
+}
+
+/// GET /proofs — list proofs, newest first, optionally filtered by status.
+///
+/// Pages are keyed on `(created_at, proof_id)`, so proofs created while
+/// paging never shift later pages.
+async fn handle_list_proofs(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ListProofsQuery>,
+) -> Result<Json<ListProofsResponse>, StatusCode> {
+    check_auth(&headers, &state)?;
+
+    let after = match query.cursor.as_deref() {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    let (after_created, after_id) = after.unzip();
+    let limit = list_limit(query.limit);
+
+    // Fetch one extra row to learn whether there is a next page.
+    let mut proofs = sqlx::query_as::<_, ProofListItem>(
+        "SELECT proof_id, status, proving_duration_ms, verified, error_message, created_at \
+         FROM verdict_proofs \
+         WHERE ($1::text IS NULL OR status = $1) \
+           AND ($2::timestamptz IS NULL OR (created_at, proof_id) < ($2, $3)) \
+         ORDER BY created_at DESC, proof_id DESC \
+         LIMIT $4"
+    )
+    .bind(&query.status)
+    .bind(after_created)
+    .bind(after_id)
+    .bind(limit + 1)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let next_cursor = if proofs.len() as i64 > limit {
+        proofs.truncate(limit as usize);
+        proofs.last().map(|p| encode_cursor(&p.created_at, &p.proof_id))
+    } else {
+        None
+    };
+    Ok(Json(ListProofsResponse { proofs, next_cursor }))
+}
+
+/// `limit` clamped to 1..=MAX_LIST_LIMIT, DEFAULT_LIST_LIMIT if absent.
+fn list_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT)
+}
+
+/// Opaque page cursor: `<created_at in microseconds>.<proof_id>`.
+fn encode_cursor(created_at: &chrono::DateTime<chrono::Utc>, proof_id: &str) -> String {
+    format!("{}.{}", created_at.timestamp_micros(), proof_id)
+}
+
+fn decode_cursor(cursor: &str) -> Option<(chrono::DateTime<chrono::Utc>, String)> {
+    let (micros, proof_id) = cursor.split_once('.')?;
+    let created_at = chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+    Some((created_at, proof_id.to_string()))
+}
+
 /// POST /prove/verify — verify a receipt.
 async fn handle_verify(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(req): Json<VerifyRequest>,
 ) -> Json<VerifyResponse> {
-    if let Err(_) = check_auth(&headers, &state) {
-        return Json(VerifyResponse {
-            valid: false,
-            verdict: None,
-            action: None,
-            concerns_hash: None,
-            error: Some("Unauthorized".to_string()),
-        });
+    if check_auth(&headers, &state).is_err() {
+        return Json(VerifyResponse::failure("Unauthorized".to_string()));
     }
 
+    Json(verify_request(state.prover.as_ref(), &req))
+}
+
+/// POST /prove/:id/verify — verify the stored receipt of a completed proof.
+///
+/// Saves clients downloading the receipt only to POST it back to
+/// `/prove/verify`. The receipt is verified against the image ID recorded
+/// when it was proven, so proofs from an earlier guest still verify.
+/// 404 if there is no completed proof with that ID.
+async fn handle_verify_stored(
+    State(state): State<Arc<AppState>>,
+    Path(proof_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<VerifyResponse>, StatusCode> {
+    check_auth(&headers, &state)?;
+
+    let row = sqlx::query_as::<_, (Option<Vec<u8>>, Option<String>)>(
+        "SELECT receipt, image_id FROM verdict_proofs WHERE proof_id = $1 AND status = 'completed'"
+    )
+    .bind(&proof_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let (receipt_bytes, image_id) = match row {
+        Some((Some(bytes), image_id)) => (bytes, image_id),
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+
+    let receipt = match prover::receipt_from_bytes_compressed(&receipt_bytes) {
+        Ok(r) => r,
+        Err(e) => return Ok(Json(VerifyResponse::failure(format!("Invalid stored receipt: {}", e)))),
+    };
+    Ok(Json(verify_receipt(state.prover.as_ref(), &receipt, image_id.as_deref())))
+}
+
+/// POST /public/verify — verify a receipt without authentication.
+///
+/// Strictly read-only: no proving and no database access, so it is safe to
+/// expose to anyone. Rate-limited per client IP.
+async fn handle_public_verify(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyRequest>,
+) -> Json<VerifyResponse> {
+    Json(verify_request(state.prover.as_ref(), &req))
+}
+
+/// Decode and verify the receipt in `req`. Shared by the authenticated and
+/// public verify endpoints.
+///
+/// An `.aipproof` bundle is verified against its embedded image ID; an
+/// `image_id` in the request must then match it.
+fn verify_request(zk_prover: &dyn prover::Prover, req: &VerifyRequest) -> VerifyResponse {
     // Decode base64 receipt
     let receipt_bytes = match base64_decode(&req.receipt) {
         Ok(b) => b,
-        Err(e) => {
-            return Json(VerifyResponse {
-                valid: false,
-                verdict: None,
-                action: None,
-                concerns_hash: None,
-                error: Some(format!("Invalid receipt encoding: {}", e)),
-            });
-        }
+        Err(e) => return VerifyResponse::failure(format!("Invalid receipt encoding: {}", e)),
     };
 
-    let receipt = match prover::receipt_from_bytes(&receipt_bytes) {
-        Ok(r) => r,
-        Err(e) => {
-            return Json(VerifyResponse {
-                valid: false,
-                verdict: None,
-                action: None,
-                concerns_hash: None,
-                error: Some(format!("Invalid receipt: {}", e)),
-            });
+    if is_bundle(&receipt_bytes) {
+        let bundle = match read_bundle(&receipt_bytes) {
+            Ok(bundle) => bundle,
+            Err(e) => return VerifyResponse::failure(format!("Invalid bundle: {}", e)),
+        };
+        let bundle_image_id = prover::image_id_to_hex(&bundle.image_id);
+        if let Some(image_id) = req.image_id.as_deref().filter(|id| !id.is_empty()) {
+            if !image_id.eq_ignore_ascii_case(&bundle_image_id) {
+                return VerifyResponse::failure(format!(
+                    "image_id {} does not match the bundle's {}",
+                    image_id, bundle_image_id
+                ));
+            }
         }
+        return verify_receipt(zk_prover, &bundle.receipt, Some(&bundle_image_id));
+    }
+
+    let receipt = match prover::receipt_from_bytes_compressed(&receipt_bytes) {
+        Ok(r) => r,
+        Err(e) => return VerifyResponse::failure(format!("Invalid receipt: {}", e)),
     };
 
-    match prover::verify_verdict_proof(&receipt) {
-        Ok(output) => Json(VerifyResponse {
-            valid: true,
-            verdict: Some(serde_json::to_string(&output.verdict).unwrap_or_default().trim_matches('"').to_string()),
-            action: Some(serde_json::to_string(&output.action).unwrap_or_default().trim_matches('"').to_string()),
-            concerns_hash: Some(output.concerns_hash),
-            error: None,
-        }),
-        Err(e) => Json(VerifyResponse {
-            valid: false,
-            verdict: None,
-            action: None,
-            concerns_hash: None,
-            error: Some(format!("Verification failed: {}", e)),
-        }),
+    verify_receipt(zk_prover, &receipt, req.image_id.as_deref())
+}
+
+/// Verify `receipt` against `image_id` (hex) when given, else the built-in
+/// guest.
+fn verify_receipt(
+    zk_prover: &dyn prover::Prover,
+    receipt: &risc0_zkvm::Receipt,
+    image_id: Option<&str>,
+) -> VerifyResponse {
+    let image_id = match image_id.filter(|id| !id.is_empty()) {
+        Some(image_id_hex) => match prover::image_id_from_hex(image_id_hex) {
+            Ok(image_id) => image_id,
+            Err(e) => return VerifyResponse::failure(format!("Invalid image_id: {}", e)),
+        },
+        None => aip_zkvm_methods::AIP_ZKVM_GUEST_ID,
+    };
+    let verified = zk_prover.verify(receipt, image_id);
+
+    match verified {
+        Ok(output) => VerifyResponse::from_output(output),
+        Err(e) => VerifyResponse::failure(format!("Verification failed: {}", e)),
     }
 }
 
-/// GET /health — health check.
+/// GET /health — liveness only: the process is up and serving requests.
 async fn handle_health() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
@@ -298,10 +1218,109 @@ async fn handle_health() -> Json<HealthResponse> {
     })
 }
 
-/// Simple base64 decode (avoiding extra deps).
+/// GET /ready — readiness: the database answers a `SELECT 1` probe and the
+/// guest can be proven (see `prover::check_proving_ready`). 503 otherwise.
+async fn handle_ready(State(state): State<Arc<AppState>>) -> (StatusCode, Json<ReadyResponse>) {
+    let mut errors = Vec::new();
+
+    let probe = sqlx::query("SELECT 1").execute(&state.db);
+    let database = match tokio::time::timeout(HEALTH_DB_TIMEOUT, probe).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            errors.push(format!("database: {}", e));
+            false
+        }
+        Err(_) => {
+            errors.push("database: probe timed out".to_string());
+            false
+        }
+    };
+
+    let prover = match tokio::task::spawn_blocking(prover::check_proving_ready).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            errors.push(format!("prover: {}", e));
+            false
+        }
+        Err(e) => {
+            errors.push(format!("prover: readiness check panicked: {}", e));
+            false
+        }
+    };
+
+    let code = if database && prover {
+        StatusCode::OK
+    } else {
+        warn!(?errors, "Readiness check failed");
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        code,
+        Json(ReadyResponse {
+            status: if code == StatusCode::OK { "ready" } else { "not_ready" }.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            database,
+            prover,
+            errors,
+        }),
+    )
+}
+
+/// GET /metrics — service gauges in Prometheus text format.
+async fn handle_metrics(State(state): State<Arc<AppState>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+        .into_response()
+}
+
+/// GET /capabilities — report the guest image ID and ELF hash.
+async fn handle_capabilities() -> Json<CapabilitiesResponse> {
+    Json(CapabilitiesResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        image_id: prover::guest_image_id_hex(),
+        guest_elf_sha256: prover::guest_elf_sha256(),
+    })
+}
+
+/// Decode a standard-alphabet base64 receipt of at most MAX_RECEIPT_BYTES.
+///
+/// Errors are phrased for API consumers rather than passed through from the
+/// base64 crate: empty input, characters outside the alphabet, and padding
+/// or length problems are each reported separately.
 fn base64_decode(input: &str) -> Result<Vec<u8>, String> {
+    use base64::DecodeError;
     use base64_engine::*;
-    STANDARD.decode(input).map_err(|e| e.to_string())
+
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("receipt is empty".to_string());
+    }
+    if input.len() / 4 * 3 > MAX_RECEIPT_BYTES {
+        return Err(format!("receipt is larger than the {} byte limit", MAX_RECEIPT_BYTES));
+    }
+    let char_at = |offset: usize| input.get(offset..).and_then(|s| s.chars().next()).unwrap_or('?');
+    STANDARD.decode(input).map_err(|e| match e {
+        DecodeError::InvalidByte(offset, b'-' | b'_') => format!(
+            "not base64: {:?} at position {} is from the URL-safe alphabet; use standard base64",
+            char_at(offset),
+            offset
+        ),
+        DecodeError::InvalidByte(offset, _) => format!(
+            "not base64: unexpected character {:?} at position {}",
+            char_at(offset),
+            offset
+        ),
+        DecodeError::InvalidLength(len) => format!(
+            "wrong padding: length {} is not a multiple of 4; the receipt may be truncated",
+            len
+        ),
+        DecodeError::InvalidPadding | DecodeError::InvalidLastSymbol(..) => {
+            "wrong padding: the trailing '=' characters or final symbol are invalid".to_string()
+        }
+    })
 }
 
 mod base64_engine {
@@ -309,14 +1328,53 @@ mod base64_engine {
     pub use base64::Engine;
 }
 
+/// Keepalive settings, read from `KEEPALIVE_ENABLED`, `KEEPALIVE_INTERVAL_SECS`
+/// and `KEEPALIVE_URL`.
+#[derive(Debug, Clone)]
+pub struct KeepaliveConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+    pub url: String,
+}
+
+impl KeepaliveConfig {
+    /// Defaults: enabled, every 30s, against our own `/health` on `port`.
+    pub fn from_env(port: u16) -> Self {
+        let enabled = std::env::var("KEEPALIVE_ENABLED")
+            .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off"))
+            .unwrap_or(true);
+        let interval = std::env::var("KEEPALIVE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&n| n > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(30));
+        let url = std::env::var("KEEPALIVE_URL")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| format!("http://127.0.0.1:{}/health", port));
+        Self { enabled, interval, url }
+    }
+}
+
+/// Periodically GET `config.url` so Fly.io sees sustained HTTP activity and
+/// doesn't auto-stop the machine while proofs are queued.
+pub async fn keepalive_task(config: KeepaliveConfig) {
+    let client = reqwest::Client::new();
+    loop {
+        tokio::time::sleep(config.interval).await;
+        if let Err(e) = client.get(&config.url).timeout(Duration::from_secs(5)).send().await {
+            warn!(url = %config.url, "Keepalive request failed: {}", e);
+        }
+    }
+}
+
 /// Row returned by the updated get_pending_proofs function.
 #[derive(sqlx::FromRow)]
 struct PendingProof {
     proof_id: String,
-    #[allow(dead_code)]
     checkpoint_id: String,
     retry_count: i32,
-    #[allow(dead_code)]
     created_at: chrono::DateTime<chrono::Utc>,
     analysis_json: Option<String>,
     thinking_hash: Option<String>,
@@ -325,36 +1383,155 @@ struct PendingProof {
     model: Option<String>,
 }
 
+/// Prove `prover::warm_up`'s built-in fixture once, holding a proving permit
+/// so it never runs alongside more proofs than the configured limit allows.
+/// Failures are logged and otherwise ignored: warm-up is only an
+/// optimization.
+pub async fn warm_up_task(state: AppState) {
+    let Ok(permit) = state.proving_permits.acquire_owned().await else {
+        return;
+    };
+    let zk_prover = state.prover.clone();
+    match prove_blocking(state.proof_timeout, permit, move || {
+        prover::warm_up(zk_prover.as_ref())
+    })
+    .await
+    {
+        Ok(elapsed) => info!(duration_ms = elapsed.as_millis() as u64, "Warm-up proof complete"),
+        Err(e) => warn!("Warm-up proof failed: {}", e),
+    }
+}
+
+/// Retry loop settings, read from `RETRY_BACKOFF_BASE_SECS`,
+/// `RETRY_MAX_ATTEMPTS`, `RETRY_INTERVAL_SECS` and `RETRY_BATCH_SIZE`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// How often the loop polls for pending proofs.
+    pub interval: Duration,
+    /// Pending proofs fetched per poll.
+    pub batch_size: i32,
+    /// Delay before the first retry; doubles with each subsequent one.
+    pub backoff_base: Duration,
+    /// Retries after which a proof is failed permanently.
+    pub max_retries: i32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            batch_size: 5,
+            backoff_base: Duration::from_secs(30),
+            max_retries: 5,
+        }
+    }
+}
+
+/// Parse a positive setting, falling back to `default` when unset. Unlike
+/// the backoff settings, a bad poll interval or batch size would stall the
+/// retry loop entirely, so these are rejected rather than ignored.
+fn parse_positive<T>(name: &str, value: Option<String>, default: T) -> Result<T, String>
+where
+    T: std::str::FromStr + PartialOrd + Default,
+{
+    match value {
+        None => Ok(default),
+        Some(v) => v
+            .trim()
+            .parse::<T>()
+            .ok()
+            .filter(|n| *n > T::default())
+            .ok_or_else(|| format!("{} must be a positive integer, got {:?}", name, v)),
+    }
+}
+
+impl RetryPolicy {
+    /// Read the policy from the environment. Fails if `RETRY_INTERVAL_SECS`
+    /// or `RETRY_BATCH_SIZE` is set but not a positive integer.
+    pub fn from_env() -> Result<Self, String> {
+        let default = Self::default();
+        Ok(Self {
+            interval: parse_positive(
+                "RETRY_INTERVAL_SECS",
+                std::env::var("RETRY_INTERVAL_SECS").ok(),
+                default.interval.as_secs(),
+            )
+            .map(Duration::from_secs)?,
+            batch_size: parse_positive(
+                "RETRY_BATCH_SIZE",
+                std::env::var("RETRY_BATCH_SIZE").ok(),
+                default.batch_size,
+            )?,
+            backoff_base: std::env::var("RETRY_BACKOFF_BASE_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default.backoff_base),
+            max_retries: std::env::var("RETRY_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse::<i32>().ok())
+                .filter(|&n| n >= 0)
+                .unwrap_or(default.max_retries),
+        })
+    }
+
+    /// Backoff before retry number `retry_count + 1`: `base * 2^retry_count`.
+    fn backoff(&self, retry_count: i32) -> chrono::Duration {
+        let factor = 1u32 << retry_count.clamp(0, 16);
+        chrono::Duration::from_std(self.backoff_base * factor).unwrap_or(chrono::Duration::MAX)
+    }
+
+    /// Whether a proof created at `created_at` and retried `retry_count`
+    /// times is due for another attempt at `now`.
+    fn is_due(
+        &self,
+        created_at: chrono::DateTime<chrono::Utc>,
+        retry_count: i32,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        created_at
+            .checked_add_signed(self.backoff(retry_count))
+            .is_none_or(|due| due <= now)
+    }
+}
+
 /// Background retry loop for pending proofs.
 ///
-/// Every 30 seconds, fetches pending proofs that have stored input data
-/// and spawns proving tasks for them — the same logic as handle_prove.
-pub async fn retry_loop(db: PgPool) {
+/// Every `policy.interval`, fetches up to `policy.batch_size` pending proofs that have stored input data
+/// and proves them with `spawn_proof`, as handle_prove does, sharing its
+/// concurrency limit. Proofs are retried with exponential
+/// backoff and failed permanently after `policy.max_retries` attempts.
+pub async fn retry_loop(state: AppState, policy: RetryPolicy) {
+    let db = state.db.clone();
     loop {
-        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-
-        // Self-ping: make an HTTP request to our own health endpoint so
-        // Fly.io sees sustained HTTP activity and doesn't auto-stop us.
-        let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
-        if let Ok(mut stream) = tokio::net::TcpStream::connect(format!("127.0.0.1:{}", port)).await {
-            use tokio::io::AsyncWriteExt;
-            let _ = stream.write_all(
-                format!("GET /health HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n", port).as_bytes()
-            ).await;
-        }
+        tokio::time::sleep(policy.interval).await;
 
         let pending = sqlx::query_as::<_, PendingProof>(
             "SELECT proof_id, checkpoint_id, retry_count, created_at, \
                     analysis_json, thinking_hash, card_hash, values_hash, model \
-             FROM get_pending_proofs(5)"
+             FROM get_pending_proofs($1)"
         )
+        .bind(policy.batch_size)
         .fetch_all(&db)
         .await;
 
         match pending {
             Ok(rows) if !rows.is_empty() => {
-                info!(count = rows.len(), "Retrying pending proofs");
+                let now = chrono::Utc::now();
                 for row in rows {
+                    if row.retry_count >= policy.max_retries {
+                        warn!(proof_id = %row.proof_id, retry_count = row.retry_count, "Giving up on proof after max retries");
+                        let _ = sqlx::query("SELECT fail_proof($1, $2)")
+                            .bind(&row.proof_id)
+                            .bind(format!("Gave up after {} retries", row.retry_count))
+                            .execute(&db)
+                            .await;
+                        continue;
+                    }
+                    if !policy.is_due(row.created_at, row.retry_count, now) {
+                        continue;
+                    }
+
                     let analysis_json = match row.analysis_json {
                         Some(v) if !v.is_empty() => v,
                         _ => {
@@ -362,91 +1539,50 @@ pub async fn retry_loop(db: PgPool) {
                             continue;
                         }
                     };
-                    let thinking_hash = row.thinking_hash.unwrap_or_default();
-                    let card_hash = row.card_hash.unwrap_or_default();
-                    let values_hash = row.values_hash.unwrap_or_default();
-                    let model = row.model.unwrap_or_else(|| "unknown".to_string());
 
-                    info!(proof_id = %row.proof_id, retry_count = row.retry_count, "Spawning retry proof");
-
-                    // Mark as proving
-                    let _ = sqlx::query(
-                        "UPDATE verdict_proofs SET status = 'proving', updated_at = now() WHERE proof_id = $1"
+                    // Claim it as proving and count the attempt. Like
+                    // `claim_proof`, the update is conditional, so a proof
+                    // re-posted to /prove since it was fetched isn't proven
+                    // twice.
+                    let claimed = sqlx::query(
+                        "UPDATE verdict_proofs SET status = 'proving', retry_count = retry_count + 1, updated_at = now() \
+                         WHERE proof_id = $1 AND status = 'pending'"
                     )
                     .bind(&row.proof_id)
                     .execute(&db)
                     .await;
-
-                    // Spawn proving task (same logic as handle_prove)
-                    let db_clone = db.clone();
-                    let proof_id = row.proof_id.clone();
-                    tokio::spawn(async move {
-                        let start = std::time::Instant::now();
-
-                        match prover::prove_verdict_derivation(
-                            &analysis_json,
-                            &thinking_hash,
-                            &card_hash,
-                            &values_hash,
-                            &model,
-                        ) {
-                            Ok((receipt, output)) => {
-                                let duration_ms = start.elapsed().as_millis() as i32;
-                                let receipt_bytes = match prover::receipt_to_bytes(&receipt) {
-                                    Ok(b) => b,
-                                    Err(e) => {
-                                        error!(proof_id = %proof_id, "Failed to serialize receipt: {}", e);
-                                        let _ = sqlx::query("SELECT fail_proof($1, $2)")
-                                            .bind(&proof_id)
-                                            .bind(format!("Receipt serialization failed: {}", e))
-                                            .execute(&db_clone)
-                                            .await;
-                                        return;
-                                    }
-                                };
-
-                                let journal_bytes = receipt.journal.bytes.clone();
-                                let verdict_str = serde_json::to_string(&output.verdict).unwrap_or_default();
-                                let image_id_hex: String = aip_zkvm_methods::AIP_ZKVM_GUEST_ID
-                                    .iter()
-                                    .flat_map(|w| w.to_le_bytes())
-                                    .map(|b| format!("{:02x}", b))
-                                    .collect();
-
-                                let verified = prover::verify_verdict_proof(&receipt).is_ok();
-
-                                info!(
-                                    proof_id = %proof_id,
-                                    verdict = %verdict_str,
-                                    duration_ms = duration_ms,
-                                    verified = verified,
-                                    "Retry proof completed"
-                                );
-
-                                let _ = sqlx::query(
-                                    "SELECT complete_proof($1, $2, $3, $4, $5, $6::numeric, $7, $8)"
-                                )
-                                .bind(&proof_id)
-                                .bind(&image_id_hex)
-                                .bind(&receipt_bytes)
-                                .bind(&journal_bytes)
-                                .bind(duration_ms)
-                                .bind(0.005f64)
-                                .bind(verified)
-                                .bind(if verified { Some(chrono::Utc::now()) } else { None })
-                                .execute(&db_clone)
-                                .await;
-                            }
-                            Err(e) => {
-                                error!(proof_id = %proof_id, "Retry proving failed: {}", e);
-                                let _ = sqlx::query("SELECT fail_proof($1, $2)")
-                                    .bind(&proof_id)
-                                    .bind(format!("Proving failed: {}", e))
-                                    .execute(&db_clone)
-                                    .await;
-                            }
+                    match claimed {
+                        Ok(r) if r.rows_affected() > 0 => {}
+                        Ok(_) => {
+                            info!(proof_id = %row.proof_id, "Skipping retry: proof already claimed");
+                            continue;
+                        }
+                        Err(e) => {
+                            error!(proof_id = %row.proof_id, "Failed to claim proof for retry: {}", e);
+                            continue;
                         }
-                    });
+                    }
+
+                    // Retries have no incoming request, so each gets its own
+                    // request ID for the proving task's logs.
+                    let request_id = uuid::Uuid::new_v4().to_string();
+                    info!(
+                        proof_id = %row.proof_id,
+                        request_id = %request_id,
+                        retry_count = row.retry_count,
+                        "Spawning retry proof"
+                    );
+                    let req = ProofRequest {
+                        proof_id: row.proof_id,
+                        checkpoint_id: row.checkpoint_id,
+                        analysis_json,
+                        thinking_hash: row.thinking_hash.unwrap_or_default(),
+                        card_hash: row.card_hash.unwrap_or_default(),
+                        values_hash: row.values_hash.unwrap_or_default(),
+                        model: row.model.unwrap_or_else(|| "unknown".to_string()),
+                        callback_url: None,
+                    };
+                    spawn_proof(&state, req, request_id);
                 }
             }
             Ok(_) => {} // No pending proofs
@@ -454,5 +1590,584 @@ pub async fn retry_loop(db: PgPool) {
                 warn!("Failed to query pending proofs: {}", e);
             }
         }
+
+        // Heartbeat for alerting: a stale timestamp means this loop has died.
+        let pending_count = sqlx::query_as::<_, (i64,)>(
+            "SELECT count(*) FROM verdict_proofs WHERE status = 'pending'"
+        )
+        .fetch_one(&db)
+        .await
+        .map(|(n,)| n)
+        .map_err(|e| warn!("Failed to count pending proofs: {}", e))
+        .ok();
+        state.metrics.record_retry_pass(chrono::Utc::now().timestamp(), pending_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload_rfc4231_vector() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_list_cursor_roundtrip() {
+        let created_at = chrono::DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap();
+        let cursor = encode_cursor(&created_at, "proof.with.dots");
+        assert_eq!(decode_cursor(&cursor), Some((created_at, "proof.with.dots".to_string())));
+        assert_eq!(decode_cursor("not-a-cursor"), None);
+        assert_eq!(decode_cursor("soon.p"), None);
+    }
+
+    #[test]
+    fn test_list_limit_is_clamped() {
+        assert_eq!(list_limit(None), DEFAULT_LIST_LIMIT);
+        assert_eq!(list_limit(Some(0)), 1);
+        assert_eq!(list_limit(Some(50)), 50);
+        assert_eq!(list_limit(Some(10_000)), MAX_LIST_LIMIT);
+    }
+
+    #[test]
+    fn test_callback_url_is_optional() {
+        let req: ProofRequest = serde_json::from_str(
+            r#"{"proof_id":"p","checkpoint_id":"c","analysis_json":"{}","thinking_hash":"t","card_hash":"c","values_hash":"v","model":"m"}"#,
+        )
+        .unwrap();
+        assert!(req.callback_url.is_none());
+    }
+
+    fn state_with_key(key: Option<&str>) -> AppState {
+        AppState {
+            db: PgPool::connect_lazy("postgres://localhost/unused").unwrap(),
+            prover_key: key.map(str::to_string),
+            proving_permits: Arc::new(Semaphore::new(1)),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            rate_limiter: None,
+            proof_timeout: DEFAULT_PROOF_TIMEOUT,
+            self_verify: true,
+            prover: Arc::new(prover::MockProver::default()),
+            metrics: Arc::new(Metrics::default()),
+        }
+    }
+
+    fn prove_request(analysis_json: String) -> ProofRequest {
+        ProofRequest {
+            proof_id: "p".to_string(),
+            checkpoint_id: "c".to_string(),
+            analysis_json,
+            thinking_hash: "t".to_string(),
+            card_hash: "c".to_string(),
+            values_hash: "v".to_string(),
+            model: "m".to_string(),
+            callback_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prove_rejects_oversized_analysis_json() {
+        let state = Arc::new(state_with_key(None));
+        let req = prove_request("x".repeat(MAX_ANALYSIS_JSON_LEN + 1));
+        let request_id = Extension(RequestId("r".to_string()));
+        let (code, message) = handle_prove(State(state), HeaderMap::new(), request_id, Json(req))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(code, StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(message.contains("analysis_json"));
+    }
+
+    #[tokio::test]
+    async fn test_prove_sync_rejects_unauthorized_and_oversized() {
+        let request_id = || Extension(RequestId("r".to_string()));
+        let state = Arc::new(state_with_key(Some("secret")));
+        let response = handle_prove_sync(State(state), HeaderMap::new(), request_id(), Json(prove_request("{}".to_string())))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let state = Arc::new(state_with_key(None));
+        let req = prove_request("x".repeat(MAX_ANALYSIS_JSON_LEN + 1));
+        let response = handle_prove_sync(State(state), HeaderMap::new(), request_id(), Json(req))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_prove_sync_fails_without_database() {
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://127.0.0.1:1/unreachable")
+            .unwrap();
+        let state = AppState {
+            db,
+            ..state_with_key(None)
+        };
+        let request_id = Extension(RequestId("r".to_string()));
+        let req = Json(prove_request("{}".to_string()));
+        let response = handle_prove_sync(State(Arc::new(state)), HeaderMap::new(), request_id, req)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_proving_task_proves_unparseable_analysis_as_malformed() {
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://127.0.0.1:1/unreachable")
+            .unwrap();
+        let state = AppState {
+            db,
+            ..state_with_key(None)
+        };
+        let garbage = "I'm sorry, I can't produce JSON for that.";
+        let mut req = prove_request(garbage.to_string());
+        req.thinking_hash = String::new();
+        req.card_hash = String::new();
+        req.values_hash = String::new();
+
+        let (status, output) = spawn_proof(&state, req, "r".to_string()).await.unwrap();
+        assert_eq!(status.status, "completed", "{:?}", status.error_message);
+        let output = output.unwrap();
+        assert_eq!(output.verdict, aip_zkvm_core::Verdict::Malformed);
+        assert_eq!(output.concerns_hash, aip_zkvm_core::hash_raw_analysis(garbage));
+    }
+
+    #[test]
+    fn test_prove_sync_response_flattens_status_and_result() {
+        let response = ProveSyncResponse {
+            status: failed_status("p", "boom".to_string()),
+            result: VerifyResponse::failure("boom".to_string()),
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["proof_id"], "p");
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["valid"], false);
+        assert_eq!(json["error"], "boom");
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_layer_rejects_large_bodies() {
+        use tower::ServiceExt;
+
+        let app = build_router(AppState {
+            max_body_bytes: 16,
+            ..state_with_key(None)
+        });
+        let request = axum::http::Request::post("/prove")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(vec![b' '; 1024]))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_accepts_either_header() {
+        let state = state_with_key(Some("secret"));
+        assert!(check_auth(&headers(&[("x-prover-key", "secret")]), &state).is_ok());
+        assert!(check_auth(&headers(&[("authorization", "Bearer secret")]), &state).is_ok());
+        assert!(check_auth(&headers(&[("authorization", "Bearer wrong")]), &state).is_err());
+        assert!(check_auth(&headers(&[("authorization", "secret")]), &state).is_err());
+        assert!(check_auth(&HeaderMap::new(), &state).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_prover_key_takes_precedence() {
+        let state = state_with_key(Some("secret"));
+        let both = headers(&[("x-prover-key", "wrong"), ("authorization", "Bearer secret")]);
+        assert!(check_auth(&both, &state).is_err());
+    }
+
+    #[test]
+    fn test_keys_match() {
+        assert!(keys_match("secret", "secret"));
+        assert!(!keys_match("secre", "secret"));
+        assert!(!keys_match("secrets", "secret"));
+        assert!(!keys_match("", "secret"));
+        assert!(!keys_match("Secret", "secret"));
+    }
+
+    #[tokio::test]
+    async fn test_requeue_requires_auth() {
+        let state = Arc::new(state_with_key(Some("secret")));
+        let (code, _) = handle_requeue(State(state), Path("p".to_string()), HeaderMap::new())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(code, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_verify_stored_requires_auth() {
+        let state = Arc::new(state_with_key(Some("secret")));
+        let code = handle_verify_stored(State(state), Path("p".to_string()), HeaderMap::new())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(code, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_verify_stored_fails_without_database() {
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://127.0.0.1:1/unreachable")
+            .unwrap();
+        let state = AppState {
+            db,
+            ..state_with_key(None)
+        };
+        let code = handle_verify_stored(State(Arc::new(state)), Path("p".to_string()), HeaderMap::new())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(code, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_check_auth_disabled_without_key() {
+        assert!(check_auth(&HeaderMap::new(), &state_with_key(None)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_health_is_liveness_only() {
+        // No database behind the pool: liveness must not depend on it.
+        let Json(body) = handle_health().await;
+        assert_eq!(body.status, "ok");
+        assert_eq!(body.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[tokio::test]
+    async fn test_ready_unavailable_without_database() {
+        let state = AppState {
+            db: PgPool::connect_lazy("postgres://127.0.0.1:1/unreachable").unwrap(),
+            ..state_with_key(None)
+        };
+        let (code, Json(body)) = handle_ready(State(Arc::new(state))).await;
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.status, "not_ready");
+        assert!(!body.database);
+        assert!(body.errors.iter().any(|e| e.starts_with("database:")), "{:?}", body.errors);
+    }
+
+    #[tokio::test]
+    async fn test_gzip_response_when_requested() {
+        use tower::ServiceExt;
+
+        let app = build_router(state_with_key(None));
+        let request = axum::http::Request::get("/capabilities")
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_returns_429_with_retry_after() {
+        use tower::ServiceExt;
+
+        let app = build_router(AppState {
+            rate_limiter: Some(Arc::new(RateLimiter::new(1, 1))),
+            ..state_with_key(Some("secret"))
+        });
+        let request = || {
+            axum::http::Request::post("/prove/verify")
+                .header("x-prover-key", "secret")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(r#"{"receipt":""}"#))
+                .unwrap()
+        };
+
+        let first = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+
+        let second = app.oneshot(request()).await.unwrap();
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(second.headers()[header::RETRY_AFTER], "60");
+    }
+
+    #[tokio::test]
+    async fn test_public_verify_skips_auth_but_is_rate_limited() {
+        use tower::ServiceExt;
+
+        let app = build_router(AppState {
+            rate_limiter: Some(Arc::new(RateLimiter::new(1, 1))),
+            ..state_with_key(Some("secret"))
+        });
+        // A made-up key must not get its own bucket on the public route.
+        let request = |key: &str| {
+            axum::http::Request::post("/public/verify")
+                .header("x-prover-key", key)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(r#"{"receipt":""}"#))
+                .unwrap()
+        };
+
+        let first = app.clone().oneshot(request("one")).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(first.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["valid"], false);
+        assert_ne!(body["error"], "Unauthorized");
+
+        let second = app.oneshot(request("two")).await.unwrap();
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_base64_decode_errors_are_descriptive() {
+        assert_eq!(base64_decode(" aGk=\n").unwrap(), b"hi");
+        assert_eq!(base64_decode("  ").unwrap_err(), "receipt is empty");
+        assert!(base64_decode("aGk*").unwrap_err().starts_with("not base64: unexpected character '*' at position 3"));
+        assert!(base64_decode("a-k=").unwrap_err().contains("URL-safe"));
+        assert!(base64_decode("aGk").unwrap_err().starts_with("wrong padding"));
+        assert!(base64_decode("aG==").unwrap_err().starts_with("wrong padding"));
+        let huge = "A".repeat((MAX_RECEIPT_BYTES / 3 + 1) * 4);
+        assert!(base64_decode(&huge).unwrap_err().contains("byte limit"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_uses_configured_prover() {
+        use base64_engine::*;
+        use prover::Prover;
+        use tower::ServiceExt;
+
+        let mock = prover::MockProver::default();
+        let analysis_json = include_str!("../../tests/fixtures/review_needed.json");
+        let (receipt, output, stats) = mock.prove(analysis_json, "", "", "", "m").unwrap();
+        assert!(self_verify(true, &mock, &receipt, &output, &stats));
+        assert!(!self_verify(false, &mock, &receipt, &output, &stats));
+
+        let receipt = STANDARD.encode(prover::receipt_to_bytes(&receipt).unwrap());
+        let request = |image_id: &str| {
+            let body = serde_json::json!({ "receipt": receipt, "image_id": image_id });
+            axum::http::Request::post("/prove/verify")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+        let app = build_router(state_with_key(None));
+
+        let response = app.clone().oneshot(request("")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["valid"], true, "{}", body);
+        assert_eq!(body["verdict"], output.verdict.to_string());
+
+        let response = app.oneshot(request(&"01".repeat(32))).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["valid"], false);
+    }
+
+    #[tokio::test]
+    async fn test_verify_accepts_bundles() {
+        use base64_engine::*;
+        use prover::Prover;
+        use tower::ServiceExt;
+
+        let mock = prover::MockProver::default();
+        let analysis_json = include_str!("../../tests/fixtures/review_needed.json");
+        let (receipt, output, _) = mock.prove(analysis_json, "", "", "", "m").unwrap();
+        let stored = prover::receipt_to_bytes(&receipt).unwrap();
+        let bundle = stored_receipt_bundle(&stored, Some(&prover::guest_image_id_hex())).unwrap();
+        assert!(is_bundle(&bundle));
+        assert_eq!(read_bundle(&bundle).unwrap().image_id, aip_zkvm_methods::AIP_ZKVM_GUEST_ID);
+
+        let bundle = STANDARD.encode(bundle);
+        let request = |path: &str, image_id: &str| {
+            let body = serde_json::json!({ "receipt": bundle, "image_id": image_id });
+            axum::http::Request::post(path)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+        let app = build_router(state_with_key(None));
+
+        for path in ["/prove/verify", "/public/verify"] {
+            let response = app.clone().oneshot(request(path, "")).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["valid"], true, "{}", body);
+            assert_eq!(body["verdict"], output.verdict.to_string());
+        }
+
+        let response = app.oneshot(request("/prove/verify", &"01".repeat(32))).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["valid"], false);
+        assert!(body["error"].as_str().unwrap().contains("does not match the bundle"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_proof_status_route_captures_id() {
+        use tower::ServiceExt;
+
+        // Without a database the handler fails with 500, which shows the
+        // route matched; an unmatched path would be 404.
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://127.0.0.1:1/unreachable")
+            .unwrap();
+        let app = build_router(AppState {
+            db,
+            ..state_with_key(None)
+        });
+        let get = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(get("/prove/proof-123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let response = app.oneshot(get("/prove/proof-123/extra")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_verify_rejects_receipt_decompressing_past_limit() {
+        use base64_engine::*;
+
+        let bomb = zstd::bulk::compress(&vec![0u8; MAX_RECEIPT_BYTES + 1], 3).unwrap();
+        let mut bytes = vec![prover::COMPRESSED_RECEIPT_TAG];
+        bytes.extend_from_slice(&bomb);
+        let req = VerifyRequest {
+            receipt: STANDARD.encode(bytes),
+            image_id: None,
+        };
+
+        let response = verify_request(&prover::MockProver::default(), &req);
+        assert!(!response.valid);
+        assert_eq!(response.error.unwrap(), "Invalid receipt: Failed to deserialize compressed receipt");
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        use tower::ServiceExt;
+
+        let state = state_with_key(Some("secret"));
+        state.metrics.record_retry_pass(1_700_000_000, Some(2));
+        let app = build_router(state);
+        let request = axum::http::Request::get("/metrics")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("aip_pending_proofs 2\n"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_echoed_or_generated() {
+        use tower::ServiceExt;
+
+        let app = build_router(state_with_key(None));
+        let get = |id: Option<&str>| {
+            let mut request = axum::http::Request::get("/capabilities");
+            if let Some(id) = id {
+                request = request.header("x-request-id", id);
+            }
+            request.body(axum::body::Body::empty()).unwrap()
+        };
+
+        let echoed = app.clone().oneshot(get(Some("abc-123"))).await.unwrap();
+        assert_eq!(echoed.headers()["x-request-id"], "abc-123");
+
+        let generated = app.oneshot(get(None)).await.unwrap();
+        let id = generated.headers()["x-request-id"].to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prove_blocking_times_out() {
+        let permits = Arc::new(Semaphore::new(1));
+        let permit = permits.clone().acquire_owned().await.unwrap();
+        let slow = prove_blocking(Duration::from_millis(10), permit, || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(())
+        });
+        assert_eq!(slow.await, Err("Proving timed out after 0s".to_string()));
+        // The timed-out thread is still proving, so it still holds the permit.
+        assert_eq!(permits.available_permits(), 0);
+        let permit = tokio::time::timeout(Duration::from_secs(5), permits.clone().acquire_owned())
+            .await
+            .expect("permit released once the thread finishes")
+            .unwrap();
+
+        let fast = prove_blocking(Duration::from_secs(5), permit, || Ok(7));
+        assert_eq!(fast.await, Ok(7));
+        assert_eq!(permits.available_permits(), 1);
+
+        let permit = permits.clone().acquire_owned().await.unwrap();
+        let failing = prove_blocking::<(), _>(Duration::from_secs(5), permit, || {
+            Err(crate::ProverError::Bundle("boom".to_string()))
+        });
+        assert!(failing.await.unwrap_err().starts_with("Proving failed: "));
+    }
+
+    #[test]
+    fn test_wants_json() {
+        let mut headers = HeaderMap::new();
+        assert!(!wants_json(&headers));
+        headers.insert(header::ACCEPT, "application/octet-stream".parse().unwrap());
+        assert!(!wants_json(&headers));
+        headers.insert(header::ACCEPT, "application/json, */*".parse().unwrap());
+        assert!(wants_json(&headers));
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let policy = RetryPolicy {
+            backoff_base: Duration::from_secs(30),
+            max_retries: 5,
+            ..RetryPolicy::default()
+        };
+        let created = chrono::Utc::now();
+        let secs = |n| chrono::Duration::seconds(n);
+
+        assert!(!policy.is_due(created, 0, created + secs(29)));
+        assert!(policy.is_due(created, 0, created + secs(30)));
+        assert!(!policy.is_due(created, 2, created + secs(119)));
+        assert!(policy.is_due(created, 2, created + secs(120)));
+    }
+
+    #[test]
+    fn test_retry_backoff_saturates() {
+        let policy = RetryPolicy::default();
+        let created = chrono::Utc::now();
+        assert!(!policy.is_due(created, i32::MAX, created + chrono::Duration::days(1)));
+    }
+
+    #[test]
+    fn test_retry_settings_validated() {
+        assert_eq!(parse_positive("RETRY_BATCH_SIZE", None, 5), Ok(5));
+        assert_eq!(parse_positive("RETRY_BATCH_SIZE", Some(" 20 ".into()), 5), Ok(20));
+        assert!(parse_positive("RETRY_BATCH_SIZE", Some("0".into()), 5).is_err());
+        assert!(parse_positive("RETRY_BATCH_SIZE", Some("-3".into()), 5).is_err());
+        let err = parse_positive("RETRY_INTERVAL_SECS", Some("soon".into()), 30u64).unwrap_err();
+        assert!(err.contains("RETRY_INTERVAL_SECS"));
     }
 }
@@ -0,0 +1,108 @@
+//! Tracing subscriber setup for the prover binary.
+//!
+//! Events are always logged to stdout (one JSON object per event with
+//! `LOG_FORMAT=json`). With the `otel` feature enabled and
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans are also exported over OTLP/HTTP:
+//! each proof gets a `proof` span with `build_env`, `prove`,
+//! `decode_journal`, `self_verify` and `db_write` children.
+
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Service name reported on exported spans.
+#[cfg(feature = "otel")]
+const SERVICE_NAME: &str = "aip-prover";
+
+/// Flushes pending spans on drop. Hold it for the lifetime of `main`.
+#[must_use = "dropping the guard shuts down span export"]
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+}
+
+impl Telemetry {
+    /// Whether spans are being exported over OTLP.
+    pub fn exporting(&self) -> bool {
+        #[cfg(feature = "otel")]
+        return self.provider.is_some();
+        #[cfg(not(feature = "otel"))]
+        return false;
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush OTLP spans: {e}");
+            }
+        }
+    }
+}
+
+/// Install the global tracing subscriber.
+///
+/// Must be called from within a Tokio runtime when OTLP export is enabled,
+/// since the batch span processor runs on it.
+pub fn init() -> Telemetry {
+    let fmt = match std::env::var("LOG_FORMAT").as_deref() {
+        Ok("json") => tracing_subscriber::fmt::layer().json().boxed(),
+        _ => tracing_subscriber::fmt::layer().boxed(),
+    };
+    let registry = tracing_subscriber::registry().with(fmt.with_filter(LevelFilter::INFO));
+
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::trace::TracerProvider as _;
+
+        let provider = otlp_provider();
+        let otel = provider.as_ref().map(|provider| {
+            tracing_opentelemetry::layer()
+                .with_tracer(provider.tracer(SERVICE_NAME))
+                .with_filter(LevelFilter::INFO)
+        });
+        registry.with(otel).init();
+        Telemetry { provider }
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.init();
+        Telemetry {}
+    }
+}
+
+/// Build a batch OTLP/HTTP tracer provider if `OTEL_EXPORTER_OTLP_ENDPOINT`
+/// is set. The exporter reads the endpoint (and the other standard
+/// `OTEL_EXPORTER_OTLP_*` variables) itself.
+#[cfg(feature = "otel")]
+fn otlp_provider() -> Option<opentelemetry_sdk::trace::TracerProvider> {
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig as _};
+    use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+    std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .ok()
+        .filter(|endpoint| !endpoint.is_empty())?;
+
+    let exporter = match SpanExporter::builder()
+        .with_http()
+        .with_timeout(std::time::Duration::from_secs(10))
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("OTLP span export disabled: {e}");
+            return None;
+        }
+    };
+
+    Some(
+        TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)]))
+            .build(),
+    )
+}
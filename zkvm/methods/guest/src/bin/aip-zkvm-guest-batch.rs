@@ -0,0 +1,32 @@
+//! RISC Zero guest program proving verdict derivation for a batch of
+//! analyses in one receipt.
+//!
+//! Each input is evaluated exactly as the single-analysis guest would, and
+//! the outputs are committed together as a `GuestOutputBatch`, so N analyses
+//! pay the fixed proving overhead once.
+
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use risc0_zkvm::guest::env;
+use aip_zkvm_core::{evaluate_batch, GuestInputBatch, JOURNAL_SCHEMA_VERSION};
+
+risc0_zkvm::guest::entry!(main);
+
+fn main() {
+    let batch: GuestInputBatch = env::read();
+    assert!(
+        batch.inputs.iter().all(|input| input.schema_version == JOURNAL_SCHEMA_VERSION),
+        "Host expects a different journal schema version"
+    );
+    // Each output commits its input's assumptions_hash, so check them as the
+    // single guest does.
+    for assumption in batch.inputs.iter().flat_map(|input| &input.assumptions) {
+        env::verify(assumption.image_id, &assumption.journal).expect("Assumption does not verify");
+    }
+
+    let output = evaluate_batch(&batch).expect("Batch size, analysis length or concern count out of range");
+    env::commit(&output);
+}
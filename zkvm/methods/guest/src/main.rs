@@ -3,7 +3,7 @@
 //! This binary runs inside the zkVM and proves that applying the AIP
 //! verdict rules to the analysis response deterministically produces
 //! the claimed verdict and action. It does NOT trust the LLM's stated
-//! verdict — it re-derives it from the concerns.
+//! verdict — it re-derives it from the concerns (see `evaluate`).
 //!
 //! Cycle budget target: ~10K RISC-V cycles.
 
@@ -12,63 +12,33 @@
 
 extern crate alloc;
 
-use alloc::string::String;
 use risc0_zkvm::guest::env;
-use aip_zkvm_core::{
-    AnalysisResponse, GuestInput, GuestOutput, MAX_EVIDENCE_LENGTH,
-    derive_verdict, map_verdict_to_action, hash_concerns,
-};
+use aip_zkvm_core::{evaluate, GuestInput, JOURNAL_SCHEMA_VERSION};
 
 risc0_zkvm::guest::entry!(main);
 
 fn main() {
     // 1. Read input from host
     let input: GuestInput = env::read();
-
-    // 2. Extract JSON from potential markdown fences
-    let json_str = extract_json(&input.analysis_json);
-
-    // 3. Parse the analysis response
-    let mut response: AnalysisResponse = serde_json::from_str(&json_str)
-        .expect("Failed to parse analysis JSON");
-
-    // 4. Truncate evidence to MAX_EVIDENCE_LENGTH (mirrors TypeScript)
-    for concern in response.concerns.iter_mut() {
-        if concern.evidence.len() > MAX_EVIDENCE_LENGTH {
-            concern.evidence = concern.evidence[..MAX_EVIDENCE_LENGTH].into();
-        }
+    assert_eq!(
+        input.schema_version, JOURNAL_SCHEMA_VERSION,
+        "Host expects a different journal schema version"
+    );
+
+    // 2. Verify the upstream claims this proof composes with. The host adds
+    //    their receipts as assumptions, so the final receipt only verifies
+    //    if they do.
+    for assumption in &input.assumptions {
+        env::verify(assumption.image_id, &assumption.journal).expect("Assumption does not verify");
     }
 
-    // 5. Re-derive verdict from concerns (does NOT trust LLM's stated verdict)
-    let verdict = derive_verdict(&response.concerns);
-
-    // 6. Map verdict to action
-    let action = map_verdict_to_action(&verdict, &response.concerns);
-
-    // 7. Hash the normalized concerns
-    let concerns_hash = hash_concerns(&response.concerns);
-
-    // 8. Commit output to journal
-    let output = GuestOutput {
-        verdict,
-        action,
-        concerns_hash,
-        thinking_hash: input.thinking_hash,
-        card_hash: input.card_hash,
-        values_hash: input.values_hash,
-        model: input.model,
-    };
+    // 3. Check the size cap, then extract, parse, truncate evidence,
+    //    re-derive the verdict, map the action and hash the concerns.
+    //    Unparseable input becomes a Malformed output rather than aborting,
+    //    so it can still be proven; analysis JSON over MAX_ANALYSIS_JSON_LEN
+    //    or responses over MAX_CONCERNS abort, so no proof is produced.
+    let output = evaluate(&input).expect("Analysis exceeds MAX_ANALYSIS_JSON_LEN or MAX_CONCERNS");
 
+    // 4. Commit output to journal
     env::commit(&output);
 }
-
-/// Extract JSON from potential markdown code fences.
-/// Finds the first '{' and last '}' in the string.
-fn extract_json(input: &str) -> String {
-    if let (Some(start), Some(end)) = (input.find('{'), input.rfind('}')) {
-        if start <= end {
-            return input[start..=end].into();
-        }
-    }
-    input.into()
-}
@@ -3,7 +3,8 @@
 //! The `include!` macro pulls in constants generated by `risc0-build`
 //! during the build step. The key exports are `AIP_ZKVM_GUEST_ELF` (the
 //! compiled RISC-V binary) and `AIP_ZKVM_GUEST_ID` (the image ID used
-//! for proof verification). Names are derived from the guest package name
-//! `aip-zkvm-guest`.
+//! for proof verification). Names are derived from the guest binary names:
+//! `aip-zkvm-guest`, and `aip-zkvm-guest-batch` for the batch guest
+//! (`AIP_ZKVM_GUEST_BATCH_ELF`, `AIP_ZKVM_GUEST_BATCH_ID`).
 
 include!(concat!(env!("OUT_DIR"), "/methods.rs"));
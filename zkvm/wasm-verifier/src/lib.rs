@@ -1,5 +1,9 @@
 use std::panic;
 
+use aip_zkvm_core::{journal_schema_version, GuestOutput, JOURNAL_SCHEMA_VERSION};
+use base64::Engine;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use wasm_bindgen::prelude::*;
 
 /// Initialize the WASM module. Sets up a panic hook that logs to the browser
@@ -26,49 +30,406 @@ pub fn version() -> String {
 /// otherwise (including on any deserialization or verification error).
 #[wasm_bindgen]
 pub fn verify_receipt(receipt_bytes: &[u8], image_id_hex: &str) -> bool {
-    // Catch any panic from the verifier and convert to `false`.
-    match panic::catch_unwind(|| verify_receipt_inner(receipt_bytes, image_id_hex)) {
-        Ok(result) => result,
-        Err(_) => {
-            log("verify_receipt: caught panic during verification");
+    verify_receipt_guarded(receipt_bytes, image_id_hex).valid
+}
+
+/// Verify a base64-encoded receipt, for Node callers holding the server's
+/// `VerifyRequest.receipt` string.
+///
+/// `receipt_b64` is standard base64 (with padding) of the bincode receipt;
+/// surrounding whitespace is ignored. Returns `false` on bad base64 as well
+/// as on any error `verify_receipt` would reject.
+#[wasm_bindgen]
+pub fn verify_receipt_from_base64(receipt_b64: &str, image_id_hex: &str) -> bool {
+    match decode_base64_receipt(receipt_b64) {
+        Ok(bytes) => verify_receipt_guarded(&bytes, image_id_hex).valid,
+        Err(e) => {
+            log(&format!("verify_receipt_from_base64: {e}"));
             false
         }
     }
 }
 
+/// Verify a RISC Zero receipt and report which stage failed, if any.
+///
+/// Returns a JS object `{ valid, stage, kind, error }` where `kind` is the
+/// receipt kind (`"composite"`, `"succinct"`, `"groth16"` or `"fake"`) once
+/// the receipt has been deserialized, and `stage` is one of
+/// `"decode"` (bad image ID hex), `"deserialize"` (bad receipt bytes),
+/// `"verify"` (proof rejected, or accepted when `valid` is true) or
+/// `"panic"` (the verifier panicked).
+#[wasm_bindgen]
+pub fn verify_receipt_detailed(receipt_bytes: &[u8], image_id_hex: &str) -> JsValue {
+    to_js(&verify_receipt_guarded(receipt_bytes, image_id_hex))
+}
+
+/// Verify a receipt against a list of accepted image IDs.
+///
+/// The receipt is deserialized once and checked against each image ID in
+/// order. Returns the index of the first ID it verifies against, or `-1` if
+/// none match (including on malformed input or a caught panic). Malformed
+/// entries in `image_ids_hex` are skipped.
+#[wasm_bindgen]
+pub fn verify_receipt_any(receipt_bytes: &[u8], image_ids_hex: Vec<String>) -> i32 {
+    match panic::catch_unwind(|| verify_any_inner(receipt_bytes, &image_ids_hex)) {
+        Ok(Ok(Some(index))) => index as i32,
+        Ok(Ok(None)) => {
+            log("verify_receipt_any: no accepted image ID matched");
+            -1
+        }
+        Ok(Err(details)) => {
+            log(&format!("verify_receipt_any: {}", details.error.unwrap_or_default()));
+            -1
+        }
+        Err(_) => {
+            log("verify_receipt_any: caught panic during verification");
+            -1
+        }
+    }
+}
+
+/// Return the kind of a serialized receipt: `"composite"`, `"succinct"`,
+/// `"groth16"` or `"fake"`, or `undefined` if the bytes do not deserialize.
+///
+/// All kinds are accepted by `verify_receipt`; compressed (succinct/Groth16)
+/// receipts are much smaller to ship to the browser.
+#[wasm_bindgen]
+pub fn receipt_kind(receipt_bytes: &[u8]) -> Option<String> {
+    let receipt: risc0_zkvm::Receipt = bincode::deserialize(receipt_bytes).ok()?;
+    Some(ReceiptKind::of(&receipt).as_str().to_string())
+}
+
+/// Decode the `GuestOutput` committed to a receipt's journal.
+///
+/// This does NOT verify the proof — use `verify_and_decode` when the output
+/// needs to be trusted. Returns `null` on any deserialization or decode error.
+#[wasm_bindgen]
+pub fn decode_journal(receipt_bytes: &[u8]) -> JsValue {
+    match panic::catch_unwind(|| decode_journal_inner(receipt_bytes)) {
+        Ok(Ok(output)) => to_js(&output),
+        Ok(Err(e)) => {
+            log(&format!("decode_journal: {e}"));
+            JsValue::NULL
+        }
+        Err(_) => {
+            log("decode_journal: caught panic during decoding");
+            JsValue::NULL
+        }
+    }
+}
+
+/// SHA-256 of a receipt's raw journal bytes, hex-encoded.
+///
+/// Lets clients that already trust a receipt pin its journal out-of-band and
+/// detect tampering without re-verifying. Nothing is verified here. Returns
+/// `null` if the receipt cannot be deserialized.
+#[wasm_bindgen]
+pub fn journal_hash(receipt_bytes: &[u8]) -> Option<String> {
+    match panic::catch_unwind(|| journal_hash_inner(receipt_bytes)) {
+        Ok(Ok(hash)) => Some(hash),
+        Ok(Err(e)) => {
+            log(&format!("journal_hash: {e}"));
+            None
+        }
+        Err(_) => {
+            log("journal_hash: caught panic during hashing");
+            None
+        }
+    }
+}
+
+/// Verify a receipt and decode its journal in a single pass.
+///
+/// Returns a JS object `{ valid, stage, error, output }`. `output` holds the
+/// decoded `GuestOutput` only when `valid` is true, and is `null` otherwise.
+#[wasm_bindgen]
+pub fn verify_and_decode(receipt_bytes: &[u8], image_id_hex: &str) -> JsValue {
+    let result = match panic::catch_unwind(|| verify_and_decode_inner(receipt_bytes, image_id_hex)) {
+        Ok(result) => result,
+        Err(_) => VerifiedOutput {
+            details: VerificationDetails::failed(
+                VerifyStage::Panic,
+                "caught panic during verification".to_string(),
+            ),
+            output: None,
+        },
+    };
+
+    if let Some(err) = &result.details.error {
+        log(&format!("verify_and_decode: {err}"));
+    }
+    to_js(&result)
+}
+
+/// Verify many receipts against the same image ID.
+///
+/// `receipts` is an array of `Uint8Array`s. The image ID is decoded once and
+/// reused for every entry. Returns an array of `{ valid, stage, kind, error }`
+/// objects (see `verify_receipt_detailed`) in the same order as the input;
+/// a panic while verifying one receipt is reported in its slot only.
+#[wasm_bindgen]
+pub fn verify_receipts_batch(receipts: js_sys::Array, image_id_hex: &str) -> JsValue {
+    let receipts: Vec<Vec<u8>> = receipts
+        .iter()
+        .map(|value| js_sys::Uint8Array::new(&value).to_vec())
+        .collect();
+
+    let results = verify_batch_inner(&receipts, image_id_hex);
+    for (i, details) in results.iter().enumerate() {
+        if let Some(err) = &details.error {
+            log(&format!("verify_receipts_batch[{i}]: {err}"));
+        }
+    }
+    to_js(&results)
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
-fn verify_receipt_inner(receipt_bytes: &[u8], image_id_hex: &str) -> bool {
-    // Decode the image ID from hex to [u32; 8].
-    let image_id = match decode_image_id(image_id_hex) {
-        Some(id) => id,
-        None => {
-            log("verify_receipt: invalid image_id_hex");
-            return false;
+/// Stage of the verification pipeline a result refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifyStage {
+    Decode,
+    Deserialize,
+    Verify,
+    Panic,
+}
+
+/// Kind of seal carried by a receipt.
+///
+/// `Receipt::verify` dispatches on the inner receipt, so composite STARK,
+/// succinct STARK and Groth16 receipts all verify through the same path; the
+/// image ID is checked against the claim regardless of kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptKind {
+    Composite,
+    Succinct,
+    Groth16,
+    Fake,
+    /// A kind added by a newer `risc0-zkvm` than this verifier knows about.
+    Unknown,
+}
+
+impl ReceiptKind {
+    fn of(receipt: &risc0_zkvm::Receipt) -> Self {
+        use risc0_zkvm::InnerReceipt;
+
+        match &receipt.inner {
+            InnerReceipt::Composite(_) => Self::Composite,
+            InnerReceipt::Succinct(_) => Self::Succinct,
+            InnerReceipt::Groth16(_) => Self::Groth16,
+            InnerReceipt::Fake(_) => Self::Fake,
+            _ => Self::Unknown,
         }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Composite => "composite",
+            Self::Succinct => "succinct",
+            Self::Groth16 => "groth16",
+            Self::Fake => "fake",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Outcome of a single receipt verification.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationDetails {
+    pub valid: bool,
+    pub stage: VerifyStage,
+    pub kind: Option<ReceiptKind>,
+    pub error: Option<String>,
+}
+
+impl VerificationDetails {
+    fn failed(stage: VerifyStage, error: String) -> Self {
+        Self { valid: false, stage, kind: None, error: Some(error) }
+    }
+}
+
+/// Verification outcome together with the decoded journal.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifiedOutput {
+    #[serde(flatten)]
+    pub details: VerificationDetails,
+    pub output: Option<GuestOutput>,
+}
+
+/// Run verification with the panic guard, logging any failure.
+fn verify_receipt_guarded(receipt_bytes: &[u8], image_id_hex: &str) -> VerificationDetails {
+    // Catch any panic from the verifier and report it as its own stage.
+    let details = match panic::catch_unwind(|| verify_receipt_inner(receipt_bytes, image_id_hex)) {
+        Ok(details) => details,
+        Err(_) => VerificationDetails::failed(
+            VerifyStage::Panic,
+            "caught panic during verification".to_string(),
+        ),
     };
 
-    // Deserialize the receipt from bincode.
-    let receipt: risc0_zkvm::Receipt = match bincode::deserialize(receipt_bytes) {
-        Ok(r) => r,
-        Err(e) => {
-            log(&format!("verify_receipt: deserialization failed: {e}"));
-            return false;
+    if let Some(err) = &details.error {
+        log(&format!("verify_receipt: {err}"));
+    }
+    details
+}
+
+fn verify_receipt_inner(receipt_bytes: &[u8], image_id_hex: &str) -> VerificationDetails {
+    match decode_receipt(receipt_bytes, image_id_hex) {
+        Ok((receipt, image_id)) => verify_decoded(&receipt, image_id),
+        Err(details) => details,
+    }
+}
+
+fn verify_and_decode_inner(receipt_bytes: &[u8], image_id_hex: &str) -> VerifiedOutput {
+    let (receipt, image_id) = match decode_receipt(receipt_bytes, image_id_hex) {
+        Ok(decoded) => decoded,
+        Err(details) => return VerifiedOutput { details, output: None },
+    };
+
+    let details = verify_decoded(&receipt, image_id);
+    if !details.valid {
+        return VerifiedOutput { details, output: None };
+    }
+
+    match decode_output(&receipt.journal) {
+        Ok(output) => VerifiedOutput { details, output: Some(output) },
+        Err(e) => VerifiedOutput {
+            details: VerificationDetails {
+                valid: false,
+                error: Some(e),
+                ..details
+            },
+            output: None,
+        },
+    }
+}
+
+fn decode_base64_receipt(receipt_b64: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(receipt_b64.trim())
+        .map_err(|e| format!("invalid base64: {e}"))
+}
+
+fn journal_hash_inner(receipt_bytes: &[u8]) -> Result<String, String> {
+    let receipt: risc0_zkvm::Receipt = bincode::deserialize(receipt_bytes)
+        .map_err(|e| format!("deserialization failed: {e}"))?;
+    Ok(hex::encode(Sha256::digest(&receipt.journal.bytes)))
+}
+
+fn decode_journal_inner(receipt_bytes: &[u8]) -> Result<GuestOutput, String> {
+    let receipt: risc0_zkvm::Receipt = bincode::deserialize(receipt_bytes)
+        .map_err(|e| format!("deserialization failed: {e}"))?;
+    decode_output(&receipt.journal)
+}
+
+/// Decode a journal, refusing layouts other than `JOURNAL_SCHEMA_VERSION`.
+fn decode_output(journal: &risc0_zkvm::Journal) -> Result<GuestOutput, String> {
+    match journal_schema_version(&journal.bytes) {
+        Some(JOURNAL_SCHEMA_VERSION) => {}
+        Some(found) => {
+            return Err(format!(
+                "unsupported journal schema version {found} (expected {JOURNAL_SCHEMA_VERSION})"
+            ))
         }
+        None => return Err("journal decode failed: journal is empty".to_string()),
+    }
+    journal
+        .decode()
+        .map_err(|e| format!("journal decode failed: {e}"))
+}
+
+/// Decode the image ID and deserialize the receipt, reporting the failing
+/// stage on error.
+fn decode_receipt(
+    receipt_bytes: &[u8],
+    image_id_hex: &str,
+) -> Result<(risc0_zkvm::Receipt, [u32; 8]), VerificationDetails> {
+    let image_id = decode_image_id_checked(image_id_hex)?;
+    let receipt = deserialize_receipt(receipt_bytes)?;
+    Ok((receipt, image_id))
+}
+
+/// Decode the image ID from hex to [u32; 8].
+fn decode_image_id_checked(image_id_hex: &str) -> Result<[u32; 8], VerificationDetails> {
+    decode_image_id(image_id_hex).ok_or_else(|| {
+        VerificationDetails::failed(VerifyStage::Decode, "invalid image_id_hex".to_string())
+    })
+}
+
+/// Deserialize the receipt from bincode.
+fn deserialize_receipt(receipt_bytes: &[u8]) -> Result<risc0_zkvm::Receipt, VerificationDetails> {
+    bincode::deserialize(receipt_bytes).map_err(|e| {
+        VerificationDetails::failed(
+            VerifyStage::Deserialize,
+            format!("deserialization failed: {e}"),
+        )
+    })
+}
+
+/// Find the first image ID the receipt verifies against.
+fn verify_any_inner(
+    receipt_bytes: &[u8],
+    image_ids_hex: &[String],
+) -> Result<Option<usize>, VerificationDetails> {
+    let receipt = deserialize_receipt(receipt_bytes)?;
+    Ok(image_ids_hex.iter().position(|hex| {
+        decode_image_id(hex).is_some_and(|id| verify_decoded(&receipt, id).valid)
+    }))
+}
+
+/// Verify every receipt against one decoded image ID, isolating panics so a
+/// single bad entry only fails its own slot.
+fn verify_batch_inner(receipts: &[Vec<u8>], image_id_hex: &str) -> Vec<VerificationDetails> {
+    let image_id = match decode_image_id_checked(image_id_hex) {
+        Ok(id) => id,
+        Err(details) => return vec![details; receipts.len()],
     };
 
-    // Verify the STARK proof.
+    receipts
+        .iter()
+        .map(|bytes| {
+            let verify_one = || match deserialize_receipt(bytes) {
+                Ok(receipt) => verify_decoded(&receipt, image_id),
+                Err(details) => details,
+            };
+            panic::catch_unwind(verify_one).unwrap_or_else(|_| {
+                VerificationDetails::failed(
+                    VerifyStage::Panic,
+                    "caught panic during verification".to_string(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Verify the proof of an already-deserialized receipt of any kind.
+fn verify_decoded(receipt: &risc0_zkvm::Receipt, image_id: [u32; 8]) -> VerificationDetails {
+    let kind = Some(ReceiptKind::of(receipt));
     match receipt.verify(image_id) {
-        Ok(()) => true,
-        Err(e) => {
-            log(&format!("verify_receipt: verification failed: {e}"));
-            false
-        }
+        Ok(()) => VerificationDetails { valid: true, stage: VerifyStage::Verify, kind, error: None },
+        Err(e) => VerificationDetails {
+            kind,
+            ..VerificationDetails::failed(
+                VerifyStage::Verify,
+                format!("{} verification failed: {e}", kind.map_or("receipt", ReceiptKind::as_str)),
+            )
+        },
     }
 }
 
+/// Convert a serializable value into a plain JS object via JSON, returning
+/// `null` if serialization fails.
+fn to_js<T: Serialize>(value: &T) -> JsValue {
+    serde_json::to_string(value)
+        .ok()
+        .and_then(|json| js_sys::JSON::parse(&json).ok())
+        .unwrap_or(JsValue::NULL)
+}
+
 /// Decode a 64-character hex string into the `[u32; 8]` image ID format that
 /// `risc0_zkvm::Receipt::verify` expects.
 fn decode_image_id(hex_str: &str) -> Option<[u32; 8]> {
@@ -110,3 +471,206 @@ fn console_error_panic_hook(info: &panic::PanicHookInfo) {
 fn log(msg: &str) {
     web_sys::console::warn_1(&JsValue::from_str(msg));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE_ID_HEX: &str =
+        "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+
+    fn sample_output() -> GuestOutput {
+        GuestOutput {
+            schema_version: aip_zkvm_core::JOURNAL_SCHEMA_VERSION,
+            verdict: aip_zkvm_core::Verdict::ReviewNeeded,
+            action: aip_zkvm_core::Action::LogAndContinue,
+            confidence_bps: 9_500,
+            concerns_hash: "c".repeat(64),
+            hash_algo: Default::default(),
+            max_evidence_length: aip_zkvm_core::MAX_EVIDENCE_LENGTH as u32,
+            policy_hash: aip_zkvm_core::VerdictPolicy::default().policy_hash(),
+            thinking_hash: "t".repeat(64),
+            card_hash: "a".repeat(64),
+            values_hash: "v".repeat(64),
+            model: "test-model".to_string(),
+            assumptions_hash: String::new(),
+            stated_verdict_matches: true,
+        }
+    }
+
+    /// Build a fake (unproven) receipt whose journal commits `output`.
+    fn fake_receipt_bytes(output: &GuestOutput) -> Vec<u8> {
+        use risc0_zkvm::{FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+        let journal: Vec<u8> = words_to_bytes(&risc0_zkvm::serde::to_vec(output).unwrap());
+        let claim = ReceiptClaim::ok(decode_image_id(IMAGE_ID_HEX).unwrap(), journal.clone());
+        let receipt = Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal);
+        bincode::serialize(&receipt).unwrap()
+    }
+
+    fn words_to_bytes(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_journal_hash_is_sha256_of_journal() {
+        let output = sample_output();
+        let journal = words_to_bytes(&risc0_zkvm::serde::to_vec(&output).unwrap());
+        let hash = journal_hash_inner(&fake_receipt_bytes(&output)).expect("hashes");
+        assert_eq!(hash, hex::encode(Sha256::digest(&journal)));
+        assert_eq!(hash.len(), 64);
+
+        let mut other = sample_output();
+        other.model = "other-model".to_string();
+        assert_ne!(journal_hash_inner(&fake_receipt_bytes(&other)).unwrap(), hash);
+    }
+
+    #[test]
+    fn test_journal_hash_rejects_garbage() {
+        assert!(journal_hash_inner(&[0xff; 16]).is_err());
+    }
+
+    #[test]
+    fn test_decode_journal_roundtrip() {
+        let output = sample_output();
+        let decoded = decode_journal_inner(&fake_receipt_bytes(&output)).expect("decodes");
+        assert_eq!(decoded.verdict, output.verdict);
+        assert_eq!(decoded.action, output.action);
+        assert_eq!(decoded.concerns_hash, output.concerns_hash);
+        assert_eq!(decoded.model, output.model);
+    }
+
+    #[test]
+    fn test_decode_journal_rejects_unknown_schema_version() {
+        let output = GuestOutput {
+            schema_version: JOURNAL_SCHEMA_VERSION + 1,
+            ..sample_output()
+        };
+        let err = decode_journal_inner(&fake_receipt_bytes(&output)).unwrap_err();
+        assert!(err.contains("unsupported journal schema version"), "{err}");
+    }
+
+    #[test]
+    fn test_decode_journal_rejects_garbage() {
+        assert!(decode_journal_inner(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_verify_and_decode_withholds_unverified_output() {
+        // A fake receipt must not verify outside dev mode, so no output is returned.
+        let result = verify_and_decode_inner(&fake_receipt_bytes(&sample_output()), IMAGE_ID_HEX);
+        assert!(!result.details.valid);
+        assert_eq!(result.details.stage, VerifyStage::Verify);
+        assert!(result.output.is_none());
+    }
+
+    #[test]
+    fn test_decode_base64_receipt() {
+        assert!(decode_base64_receipt("not base64!").is_err());
+
+        let bytes = fake_receipt_bytes(&sample_output());
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+        let decoded = decode_base64_receipt(&format!(" {encoded}\n")).unwrap();
+        assert_eq!(decoded, bytes);
+        // Decodes fine, but a fake receipt must not verify outside dev mode.
+        assert!(!verify_receipt_inner(&decoded, IMAGE_ID_HEX).valid);
+    }
+
+    #[test]
+    fn test_receipt_kind_fake() {
+        let bytes = fake_receipt_bytes(&sample_output());
+        assert_eq!(receipt_kind(&bytes).as_deref(), Some("fake"));
+        assert_eq!(verify_receipt_inner(&bytes, IMAGE_ID_HEX).kind, Some(ReceiptKind::Fake));
+        assert!(receipt_kind(&[0xff]).is_none());
+    }
+
+    /// Verify a receipt fixture of the given kind. Fixtures live in
+    /// `tests/fixtures/<kind>.bin` with the guest image ID in
+    /// `tests/fixtures/image_id.txt`. They are not checked in yet: generate
+    /// them with the host's `wasm_verifier_fixtures` example, which needs the
+    /// RISC Zero toolchain and Docker.
+    fn verify_fixture(kind: ReceiptKind) {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let missing = "fixture missing; see the host's `wasm_verifier_fixtures` example";
+        let bytes = std::fs::read(format!("{dir}/{}.bin", kind.as_str())).expect(missing);
+        let image_id = std::fs::read_to_string(format!("{dir}/image_id.txt")).expect(missing);
+
+        let details = verify_receipt_inner(&bytes, image_id.trim());
+        assert_eq!(details.kind, Some(kind));
+        assert!(details.valid, "{:?}", details.error);
+    }
+
+    #[test]
+    #[ignore] // Requires fixtures from the host's `wasm_verifier_fixtures` example
+    fn test_verify_succinct_fixture() {
+        verify_fixture(ReceiptKind::Succinct);
+    }
+
+    #[test]
+    #[ignore] // Requires fixtures from the host's `wasm_verifier_fixtures` example
+    fn test_verify_groth16_fixture() {
+        verify_fixture(ReceiptKind::Groth16);
+    }
+
+    #[test]
+    fn test_batch_reports_each_entry() {
+        let receipts = vec![fake_receipt_bytes(&sample_output()), vec![0x00, 0x01]];
+        let results = verify_batch_inner(&receipts, IMAGE_ID_HEX);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].stage, VerifyStage::Verify);
+        assert_eq!(results[0].kind, Some(ReceiptKind::Fake));
+        assert_eq!(results[1].stage, VerifyStage::Deserialize);
+        assert!(results.iter().all(|r| !r.valid));
+    }
+
+    #[test]
+    fn test_batch_bad_image_id_fails_every_entry() {
+        let receipts = vec![vec![], vec![]];
+        let results = verify_batch_inner(&receipts, "bad");
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.stage == VerifyStage::Decode));
+    }
+
+    #[test]
+    fn test_verify_any_no_match() {
+        let bytes = fake_receipt_bytes(&sample_output());
+        let ids = vec!["bad".to_string(), IMAGE_ID_HEX.to_string()];
+        assert_eq!(verify_any_inner(&bytes, &ids).unwrap(), None);
+        assert_eq!(verify_any_inner(&bytes, &[]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_verify_any_bad_receipt() {
+        let ids = vec![IMAGE_ID_HEX.to_string()];
+        let err = verify_any_inner(&[0x01], &ids).unwrap_err();
+        assert_eq!(err.stage, VerifyStage::Deserialize);
+    }
+
+    #[test]
+    fn test_decode_image_id_little_endian() {
+        let id = decode_image_id(IMAGE_ID_HEX).expect("valid hex");
+        assert_eq!(id[0], u32::from_le_bytes([0x01, 0x02, 0x03, 0x04]));
+        assert_eq!(id[7], u32::from_le_bytes([0x1d, 0x1e, 0x1f, 0x20]));
+    }
+
+    #[test]
+    fn test_decode_image_id_rejects_bad_input() {
+        assert!(decode_image_id("abcd").is_none());
+        assert!(decode_image_id(&"zz".repeat(32)).is_none());
+    }
+
+    #[test]
+    fn test_detailed_stage_decode() {
+        let details = verify_receipt_inner(&[], "not-hex");
+        assert!(!details.valid);
+        assert_eq!(details.stage, VerifyStage::Decode);
+        assert!(details.error.is_some());
+    }
+
+    #[test]
+    fn test_detailed_stage_deserialize() {
+        let details = verify_receipt_inner(&[0xde, 0xad], IMAGE_ID_HEX);
+        assert!(!details.valid);
+        assert_eq!(details.stage, VerifyStage::Deserialize);
+    }
+}